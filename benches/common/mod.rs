@@ -0,0 +1,25 @@
+use fights::{envs::BaseEnv, puoribor};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+// the states early in the seeded random games, which have some walls on the board and in hand
+pub fn random_states() -> Vec<puoribor::State> {
+    (0..8)
+        .map(|seed| {
+            let mut state = puoribor::Env::initialize_state();
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            for _ in 0..10 {
+                let agent_id = state.to_move();
+                let actions = puoribor::Env::legal_actions(&state, agent_id);
+                if state.is_win().is_over() || actions.is_empty() {
+                    break;
+                }
+
+                let action = actions[rng.gen_range(0..actions.len())].clone();
+                state = puoribor::Env::step(state, agent_id, action).unwrap();
+            }
+
+            state
+        })
+        .collect()
+}
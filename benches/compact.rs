@@ -0,0 +1,81 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fights::puoribor::{self, ActionType, CompactState};
+
+mod common;
+
+use common::random_states;
+
+fn legal_actions(c: &mut Criterion) {
+    let states = random_states();
+    let compacts = states
+        .iter()
+        .map(CompactState::from_position)
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("legal_actions");
+
+    group.bench_function("state", |b| {
+        b.iter(|| {
+            for state in &states {
+                black_box(puoribor::Env::legal_actions(state, state.to_move()));
+            }
+        })
+    });
+    group.bench_function("compact", |b| {
+        b.iter(|| {
+            for compact in &compacts {
+                black_box(compact.legal_actions(compact.to_move()));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+// place every legal wall and rotate every legal section, where the path to win is checked by each of them
+fn path_checks(c: &mut Criterion) {
+    let states = random_states();
+    let cases = states
+        .iter()
+        .map(|state| {
+            let walls = puoribor::Env::legal_actions(state, state.to_move())
+                .into_iter()
+                .filter(|action| action.action_type() != ActionType::Move)
+                .collect::<Vec<_>>();
+
+            (state.clone(), CompactState::from_position(state), walls)
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("path_checks");
+
+    group.bench_function("state", |b| {
+        let mut cases = cases.clone();
+        b.iter(|| {
+            for (state, _, walls) in &mut cases {
+                let agent_id = state.to_move();
+                for action in walls.iter() {
+                    let undo = state.apply(agent_id, black_box(action)).unwrap();
+                    state.undo(undo);
+                }
+            }
+        })
+    });
+    group.bench_function("compact", |b| {
+        let mut cases = cases.clone();
+        b.iter(|| {
+            for (_, compact, walls) in &mut cases {
+                let agent_id = compact.to_move();
+                for action in walls.iter() {
+                    let undo = compact.apply(agent_id, black_box(action)).unwrap();
+                    compact.undo(undo);
+                }
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, legal_actions, path_checks);
+criterion_main!(benches);
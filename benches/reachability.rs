@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use fights::puoribor::{self, Action};
+
+mod common;
+
+use common::random_states;
+
+// the legal action generation before the cache, which runs the full BFS for every wall and rotation
+fn legal_actions_without_cache(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
+    let mut scratch = state.clone();
+
+    (0..Action::SPACE_SIZE)
+        .filter_map(Action::from_index)
+        .filter(|action| match scratch.apply(agent_id, action) {
+            Ok(undo) => {
+                scratch.undo(undo);
+                true
+            }
+            Err(_) => false,
+        })
+        .collect()
+}
+
+fn legal_actions(c: &mut Criterion) {
+    let states = random_states();
+
+    // both generate the same actions, only in the different order
+    for state in &states {
+        let mut actions = legal_actions_without_cache(state, state.to_move());
+        let mut cached = puoribor::Env::legal_actions(state, state.to_move());
+        actions.sort_by_key(|action| action.to_index());
+        cached.sort_by_key(|action| action.to_index());
+        assert_eq!(actions, cached);
+    }
+
+    let mut group = c.benchmark_group("legal_actions");
+
+    group.bench_function("with_cache", |b| {
+        b.iter(|| {
+            for state in &states {
+                black_box(puoribor::Env::legal_actions(state, state.to_move()));
+            }
+        })
+    });
+    group.bench_function("without_cache", |b| {
+        b.iter(|| {
+            for state in &states {
+                black_box(legal_actions_without_cache(state, state.to_move()));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, legal_actions);
+criterion_main!(benches);
@@ -1,14 +1,115 @@
 use core::fmt;
+use std::error::Error;
 
 pub trait BaseState: fmt::Display + Clone {}
 
 pub trait BaseEnv<S: BaseState, A> {
+    /// The failed reason of `step`
+    type Error: Error;
+
+    /// The name and the rule version of the environment
     fn env_id() -> (String, i32);
 
+    /// The id string of the environment, like "puoribor-v2"
+    fn id() -> String {
+        let (name, version) = Self::env_id();
+        format_env_id(&name, version)
+    }
+
+    /// Check if the id string(e.g. recorded on a game) is for this environment and rule version
+    fn is_compatible(id: &str) -> bool {
+        parse_env_id(id) == Some(Self::env_id())
+    }
+
     fn initialize_state() -> S;
 
     /// Step through the game
     ///
     /// On state S, append action (agent_id, action), then return new state if it possible, or return the failed reason
-    fn step(state: S, agent_id: usize, action: A) -> Result<S, String>;
+    fn step(state: S, agent_id: usize, action: A) -> Result<S, Self::Error>;
+}
+
+pub fn format_env_id(name: &str, version: i32) -> String {
+    format!("{}-v{}", name, version)
+}
+
+/// Parse the id string "{name}-v{version}" to (name, version)
+pub fn parse_env_id(id: &str) -> Option<(String, i32)> {
+    let (name, version) = id.rsplit_once("-v")?;
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name.to_string(), version.parse().ok()?))
+}
+
+/// The registered environment, which is found by its id string
+///
+/// The environments are static types of `BaseEnv`, so the tooling selects the type by `name` and checks the rule version by `version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvSpec {
+    pub name: &'static str,
+    pub version: i32,
+}
+
+impl EnvSpec {
+    pub fn id(&self) -> String {
+        format_env_id(self.name, self.version)
+    }
+}
+
+static REGISTRY: [EnvSpec; 1] = [EnvSpec {
+    name: "puoribor",
+    version: 2,
+}];
+
+/// Every registered environment
+pub fn registry() -> &'static [EnvSpec] {
+    &REGISTRY
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistryError {
+    /// The id is not formed like "{name}-v{version}"
+    InvalidId(String),
+    /// There is no environment with the name
+    UnknownEnv(String),
+    /// The environment exists, but not with the rule version
+    UnknownVersion { name: String, version: i32 },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::InvalidId(id) => {
+                write!(f, "invalid environment id '{}', expected 'name-vN'", id)
+            }
+            RegistryError::UnknownEnv(name) => write!(f, "unknown environment '{}'", name),
+            RegistryError::UnknownVersion { name, version } => {
+                write!(f, "environment '{}' has no version {}", name, version)
+            }
+        }
+    }
+}
+
+impl Error for RegistryError {}
+
+/// Find the registered environment by the id string
+pub fn spec(id: &str) -> Result<&'static EnvSpec, RegistryError> {
+    let (name, version) =
+        parse_env_id(id).ok_or_else(|| RegistryError::InvalidId(id.to_string()))?;
+
+    let mut specs = registry()
+        .iter()
+        .filter(|spec| spec.name == name)
+        .peekable();
+
+    if specs.peek().is_none() {
+        return Err(RegistryError::UnknownEnv(name));
+    }
+
+    specs
+        .find(|spec| spec.version == version)
+        .ok_or(RegistryError::UnknownVersion { name, version })
 }
@@ -1,14 +1,47 @@
 use std::{
     collections::VecDeque,
+    error::Error,
     fmt::{self, Debug},
-    hash::Hash,
+    hash::{Hash, Hasher},
+    mem,
+    str::FromStr,
 };
 
 use colored::*;
-use ndarray::Array2;
+use ndarray::{s, Array2, Array3, Axis};
 use serde::{Deserialize, Serialize};
 
-use crate::{envs::*, utils::*, Err};
+use crate::{envs::*, utils::*};
+use serialize::{ActionFormat, ResultFormat, StateFormat};
+
+mod agent;
+mod compact;
+mod log;
+mod mcts;
+mod position;
+mod record;
+mod search;
+mod selfplay;
+mod serialize;
+mod tournament;
+mod zobrist;
+
+pub use agent::{Agent, GreedyAgent, RandomAgent};
+pub use compact::{CompactEnv, CompactState};
+pub use log::{GameLogReader, GameLogWriter, LogError, LOG_VERSION};
+pub use mcts::{
+    random_action, Mcts, MctsConfig, MctsResult, PriorValue, Rollout, RolloutPolicy, Selection,
+};
+pub use position::ParsePositionError;
+pub use record::{GameRecord, Replay, ReplayError};
+pub use search::{evaluate, AlphaBeta, Evaluation, SearchLimits, SearchResult, WIN_SCORE};
+pub use selfplay::{
+    read_shard, self_play, self_play_with, write_shard, write_shards, Sample, SelfPlayConfig,
+    SelfPlayGame, SHARD_VERSION,
+};
+pub use tournament::{
+    Format, GameOutcome, Standing, Tournament, TournamentConfig, TournamentError, TournamentResult,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ActionType {
@@ -31,6 +64,7 @@ impl From<u8> for ActionType {
 }
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "ActionFormat", try_from = "ActionFormat")]
 pub struct Action {
     action_type: u8,
     position: u8,
@@ -47,6 +81,112 @@ impl Debug for Action {
     }
 }
 
+/*
+ * The text notation of Action
+ * - square: the file from 'a' for x = 0, and the rank from 1 for y = 0, like "e1" for (4, 0)
+ * - Move: the square to move to, like "e2"
+ * - PlaceWallHorizontally: "h" and the square of the left position, like "h d3"
+ * - PlaceWallVertically: "v" and the square of the top position, like "v d3"
+ * - RotateSection: "r" and the square of the top-left position, like "r c2"
+ */
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (x, y) = self.position();
+
+        match self.action_type() {
+            ActionType::Move => {}
+            ActionType::PlaceWallHorizontally => write!(f, "h ")?,
+            ActionType::PlaceWallVertically => write!(f, "v ")?,
+            ActionType::RotateSection => write!(f, "r ")?,
+        }
+
+        write!(f, "{}", format_square((x, y)))
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParseActionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = s.split_whitespace().collect::<Vec<_>>();
+
+        let (action_type, square) = match tokens[..] {
+            [] => return Err(ParseActionError::Empty),
+            [square] => (0, square),
+            [prefix, square] => match prefix {
+                "h" => (1, square),
+                "v" => (2, square),
+                "r" => (3, square),
+                _ => return Err(ParseActionError::UnknownType(prefix.to_string())),
+            },
+            _ => return Err(ParseActionError::TooManyTokens(s.to_string())),
+        };
+
+        let pos = parse_square(square)
+            .ok_or_else(|| ParseActionError::InvalidSquare(square.to_string()))?;
+
+        Ok(Action::new(action_type, pos))
+    }
+}
+
+// the square of the position in the text notation, like "e1" for (4, 0)
+fn format_square(pos: Position) -> String {
+    format!("{}{}", (b'a' + pos.0) as char, pos.1 + 1)
+}
+
+// parse the square in the text notation, whose position is packed in 4 bits for each axis
+fn parse_square(square: &str) -> Option<Position> {
+    let mut chars = square.chars();
+    let file = chars.next()?;
+    let rank = chars.as_str();
+
+    if !('a'..='p').contains(&file) || !rank.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    match rank.parse::<u8>() {
+        Ok(rank @ 1..=16) => Some((file as u8 - b'a', rank - 1)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseActionError {
+    /// There is nothing to parse
+    Empty,
+    /// The prefix of the action type is not one of "h", "v" and "r"
+    UnknownType(String),
+    /// The square is not a file from 'a' to 'p' followed by a rank from 1 to 16
+    InvalidSquare(String),
+    /// There are more than two tokens
+    TooManyTokens(String),
+}
+
+impl fmt::Display for ParseActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseActionError::Empty => write!(f, "the action is empty."),
+            ParseActionError::UnknownType(prefix) => write!(
+                f,
+                "unknown action type {:?}, which should be one of \"h\", \"v\" and \"r\".",
+                prefix
+            ),
+            ParseActionError::InvalidSquare(square) => write!(
+                f,
+                "invalid square {:?}, which should be a file from 'a' to 'p' and a rank from 1 to 16, like \"e2\".",
+                square
+            ),
+            ParseActionError::TooManyTokens(s) => write!(
+                f,
+                "too many tokens in {:?}, which should be like \"e2\" or \"h d3\".",
+                s
+            ),
+        }
+    }
+}
+
+impl Error for ParseActionError {}
+
 impl Action {
     #[inline]
     pub fn new(action_type: u8, pos: Position) -> Self {
@@ -65,6 +205,230 @@ impl Action {
     pub fn position(&self) -> Position {
         ((self.position >> 4) & 0b1111, self.position & 0b1111)
     }
+
+    /// The size of the flat action space, which is the (action_type, x, y) shaped (4, 9, 9)
+    pub const SPACE_SIZE: usize = 4 * 9 * 9;
+
+    /// Encode the action to the flat index `action_type * 81 + x * 9 + y`
+    ///
+    /// This is the C-order index of the (4, 9, 9) action space of the original environment, on the position of the serialized action.
+    /// So the walls are shifted same to the serialization, and it is `None` if the position is out of the 9x9 board.
+    pub fn to_index(&self) -> Option<usize> {
+        let ActionFormat(action_type, x, y) = self.clone().into();
+
+        if (0..9).contains(&x) && (0..9).contains(&y) {
+            Some(action_type as usize * 81 + x as usize * 9 + y as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Mirror the action upside down, for the flipped state from `State::flipped`
+    ///
+    /// The rotation is clockwise, so its mirror is counter-clockwise which is not an action of the game. So this is `None` for `RotateSection`, and also for the position which has no mirror on the board.
+    pub fn flipped(&self) -> Option<Self> {
+        let (x, y) = self.position();
+
+        let y = match self.action_type() {
+            ActionType::Move => 8u8.checked_sub(y)?,
+            ActionType::PlaceWallHorizontally => 9u8.checked_sub(y)?,
+            ActionType::PlaceWallVertically => 7u8.checked_sub(y)?,
+            ActionType::RotateSection => return None,
+        };
+
+        Some(Action::new(self.action_type, (x, y)))
+    }
+
+    /// Mirror the action left and right, for the mirrored state from `State::mirrored`
+    ///
+    /// Same to `flipped`, this is `None` for `RotateSection` whose mirror is counter-clockwise, and for the position which has no mirror on the board.
+    pub fn mirrored(&self) -> Option<Self> {
+        let (x, y) = self.position();
+
+        let x = match self.action_type() {
+            ActionType::Move => 8u8.checked_sub(x)?,
+            ActionType::PlaceWallHorizontally => 7u8.checked_sub(x)?,
+            ActionType::PlaceWallVertically => 9u8.checked_sub(x)?,
+            ActionType::RotateSection => return None,
+        };
+
+        Some(Action::new(self.action_type, (x, y)))
+    }
+
+    /// Decode the flat index from `to_index`
+    pub fn from_index(index: usize) -> Option<Self> {
+        if index < Action::SPACE_SIZE {
+            let format = ActionFormat(
+                (index / 81) as u8,
+                (index % 81 / 9) as i8,
+                (index % 9) as i8,
+            );
+
+            // every position on the 9x9 board is in the range of Action
+            Some(Action::try_from(format).unwrap())
+        } else {
+            None
+        }
+    }
+}
+
+/// The failed reason of `Env::step`, with the agent and the action which is rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepError {
+    /// The position of the action is outside of the board
+    OutOfBoard { agent_id: usize, action: Action },
+    /// The pawn cannot move onto the other pawn
+    OverlapPawn { agent_id: usize, action: Action },
+    /// There is a wall on the way of the pawn
+    BlockedByWall { agent_id: usize, action: Action },
+    /// The pawn can jump over the other pawn neither straightly nor diagonally
+    IllegalJump { agent_id: usize, action: Action },
+    /// The pawn should move one block, or two blocks by jumping over the other pawn
+    InvalidDistance { agent_id: usize, action: Action },
+    /// The agent does not have enough walls for the action
+    NoRemainingWalls { agent_id: usize, action: Action },
+    /// There is already a wall on the position
+    OverlapWall { agent_id: usize, action: Action },
+    /// The wall crosses the other wall, like + shape
+    Intersecting { agent_id: usize, action: Action },
+    /// The action blocks every path of a pawn to its winning area
+    WouldTrapPawn { agent_id: usize, action: Action },
+    /// It is not the turn of the agent
+    OutOfTurn { agent_id: usize, action: Action },
+}
+
+macro_rules! step_error {
+    ($kind:ident, $agent_id:expr, $action:expr) => {
+        Err(StepError::$kind {
+            agent_id: $agent_id,
+            action: $action.clone(),
+        })
+    };
+}
+
+impl StepError {
+    pub fn agent_id(&self) -> usize {
+        match self {
+            StepError::OutOfBoard { agent_id, .. }
+            | StepError::OverlapPawn { agent_id, .. }
+            | StepError::BlockedByWall { agent_id, .. }
+            | StepError::IllegalJump { agent_id, .. }
+            | StepError::InvalidDistance { agent_id, .. }
+            | StepError::NoRemainingWalls { agent_id, .. }
+            | StepError::OverlapWall { agent_id, .. }
+            | StepError::Intersecting { agent_id, .. }
+            | StepError::WouldTrapPawn { agent_id, .. }
+            | StepError::OutOfTurn { agent_id, .. } => *agent_id,
+        }
+    }
+
+    pub fn action(&self) -> &Action {
+        match self {
+            StepError::OutOfBoard { action, .. }
+            | StepError::OverlapPawn { action, .. }
+            | StepError::BlockedByWall { action, .. }
+            | StepError::IllegalJump { action, .. }
+            | StepError::InvalidDistance { action, .. }
+            | StepError::NoRemainingWalls { action, .. }
+            | StepError::OverlapWall { action, .. }
+            | StepError::Intersecting { action, .. }
+            | StepError::WouldTrapPawn { action, .. }
+            | StepError::OutOfTurn { action, .. } => action,
+        }
+    }
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action_type = self.action().action_type();
+        let wall = if action_type == ActionType::PlaceWallVertically {
+            "vertical"
+        } else {
+            "horizontal"
+        };
+
+        write!(f, "{:?}: ", action_type)?;
+
+        match self {
+            StepError::OutOfBoard { .. } => write!(f, "out of board"),
+            StepError::OverlapPawn { .. } => write!(
+                f,
+                "cannot overlap the other pawn. How about jumping over it?"
+            ),
+            StepError::BlockedByWall { .. } => write!(f, "the movement is blocked by wall."),
+            StepError::IllegalJump { .. } => write!(f, "cannot jump straightly or diagonally."),
+            StepError::InvalidDistance { .. } => {
+                write!(f, "should move one block, not zero or bigger than one.")
+            }
+            StepError::NoRemainingWalls { .. } => {
+                write!(f, "there is no remaining wall for the agent.")
+            }
+            StepError::OverlapWall { .. } => write!(f, "there is already {} wall.", wall),
+            StepError::Intersecting { .. } => {
+                write!(f, "cannot install {} wall intersecting.", wall)
+            }
+            StepError::WouldTrapPawn { .. } => {
+                write!(f, "this can make for the other player not to win.")
+            }
+            StepError::OutOfTurn { .. } => write!(f, "it is not the turn of the agent."),
+        }?;
+
+        write!(
+            f,
+            " (agent: {}, action: {:?})",
+            self.agent_id(),
+            self.action()
+        )
+    }
+}
+
+impl Error for StepError {}
+
+/// The result of the game on a state
+///
+/// It is serialized as the winner like the original Python environment, which is null if the game is not over, or "draw".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "ResultFormat", try_from = "ResultFormat")]
+pub enum GameResult {
+    /// The agent arrived at its winning area
+    Win(usize),
+    /// The game is ended by `DrawRules`
+    Draw,
+    Ongoing,
+}
+
+impl GameResult {
+    pub fn is_over(&self) -> bool {
+        *self != GameResult::Ongoing
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        match self {
+            GameResult::Win(agent_id) => Some(*agent_id),
+            _ => None,
+        }
+    }
+}
+
+/// The rules to end the game in a draw, which are all disabled by default like the original rules
+///
+/// Every wall action reduces the remaining walls, so only the moves can repeat the positions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrawRules {
+    /// The game is drawn if it reaches this ply
+    pub max_ply: Option<usize>,
+    /// The game is drawn if the same position appears this many times, like 3 for the threefold repetition
+    pub repetition: Option<usize>,
+}
+
+impl DrawRules {
+    /// The draw by 200 plies and the threefold repetition
+    pub fn standard() -> Self {
+        Self {
+            max_ply: Some(200),
+            repetition: Some(3),
+        }
+    }
 }
 
 /*
@@ -78,12 +442,44 @@ impl Action {
  *   - 2: one-hot encoded position of middle point of walls for preventing from placing a wall intersecting, on horizontal (size: (10, 10))
  *   - 3: one-hot encoded position of middle point of walls for preventing from placing a wall intersecting, on vertical (size: (10, 10))
  * - walls: the remaing walls on each player, (player 0's, player 1's)
+ * - to_move, ply: the agent to move and the number of the actions so far
+ * - analysis: if the actions are allowed regardless of the turn
+ * - draw_rules, history: the rules to draw, and the zobrist hashes of the positions since the last wall action for the repetition
  */
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(into = "StateFormat", try_from = "StateFormat")]
 pub struct State {
     pub players: [(u8, u8); 2],
     pub board: [Array2<u8>; 4],
     pub remaining_walls: [u8; 2],
+    to_move: usize,
+    ply: usize,
+    analysis: bool,
+    draw_rules: DrawRules,
+    history: Vec<u64>,
+    zobrist: u64,
+}
+
+// the states are same if their positions are same, so the ply, the analysis mode and the draw rules are ignored like the zobrist hash
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.players == other.players
+            && self.board == other.board
+            && self.remaining_walls == other.remaining_walls
+            && self.to_move == other.to_move
+    }
+}
+
+impl Eq for State {}
+
+// the fields of `PartialEq` are hashed instead of the zobrist hash, which is stale if the public fields are modified directly
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.players.hash(state);
+        self.board.hash(state);
+        self.remaining_walls.hash(state);
+        self.to_move.hash(state);
+    }
 }
 
 impl fmt::Display for State {
@@ -92,28 +488,253 @@ impl fmt::Display for State {
     }
 }
 
+impl Default for State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl State {
     pub fn new() -> Self {
-        Self {
-            players: [(4, 0), (4, 8)],
-            board: [
+        State::from_fields(
+            [(4, 0), (4, 8)],
+            [
                 Array2::zeros([9, 10]),
                 Array2::zeros([10, 9]),
                 Array2::zeros([10, 10]),
                 Array2::zeros([10, 10]),
             ],
-            remaining_walls: [10, 10],
+            [10, 10],
+        )
+    }
+
+    fn from_fields(
+        players: [Position; 2],
+        board: [Array2<u8>; 4],
+        remaining_walls: [u8; 2],
+    ) -> Self {
+        let mut state = Self {
+            players,
+            board,
+            remaining_walls,
+            to_move: 0,
+            ply: 0,
+            analysis: false,
+            draw_rules: DrawRules::default(),
+            history: Vec::new(),
+            zobrist: 0,
+        };
+        state.refresh_zobrist();
+
+        state
+    }
+
+    /// The zobrist hash of the state, like for the transposition table
+    ///
+    /// It is updated incrementally by `apply`, `undo` and `Env::step`. If the public fields are modified directly, `refresh_zobrist` should be called.
+    #[inline]
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    /// Compute the zobrist hash from scratch
+    pub fn refresh_zobrist(&mut self) {
+        let mut hash = 0;
+
+        for agent_id in 0..2 {
+            hash ^= zobrist::player(agent_id, self.players[agent_id]);
+            hash ^= zobrist::remaining_walls(agent_id, self.remaining_walls[agent_id]);
+        }
+
+        for (channel, board) in self.board.iter().enumerate() {
+            for (pos, &value) in board.indexed_iter() {
+                if value == 1 {
+                    hash ^= zobrist::wall(channel, pos);
+                }
+            }
         }
+
+        self.zobrist = hash ^ zobrist::to_move(self.to_move);
     }
 
-    pub fn is_win(&self) -> isize {
+    /// The agent who should do the next action
+    #[inline]
+    pub fn to_move(&self) -> usize {
+        self.to_move
+    }
+
+    /// The number of the actions applied from the initial state
+    #[inline]
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Set the agent to move and the ply, for setting up the position
+    ///
+    /// It panics if the agent to move is neither 0 nor 1.
+    pub fn set_turn(&mut self, to_move: usize, ply: usize) {
+        assert!(to_move < 2, "the agent {} cannot move.", to_move);
+        Board::set_turn(self, to_move, ply);
+    }
+
+    /// If the actions are allowed regardless of the turn
+    #[inline]
+    pub fn is_analysis(&self) -> bool {
+        self.analysis
+    }
+
+    /// Allow the actions of any agent regardless of the turn, for analyzing the positions
+    ///
+    /// The agent to move is still the other agent of the last action.
+    pub fn set_analysis(&mut self, analysis: bool) {
+        self.analysis = analysis;
+    }
+
+    #[inline]
+    pub fn draw_rules(&self) -> DrawRules {
+        self.draw_rules
+    }
+
+    pub fn set_draw_rules(&mut self, draw_rules: DrawRules) {
+        self.draw_rules = draw_rules;
+    }
+
+    /// The number of times the position has appeared, including now
+    ///
+    /// The positions are remembered by `apply` and `Env::step` only, so it is 1 after the public fields are modified directly.
+    pub fn repetitions(&self) -> usize {
+        1 + self
+            .history
+            .iter()
+            .filter(|&&hash| hash == self.zobrist)
+            .count()
+    }
+
+    /// The result of the game, where a win is prior to the draw rules
+    pub fn is_win(&self) -> GameResult {
         if self.players[0].1 == 8 {
-            0
+            GameResult::Win(0)
         } else if self.players[1].1 == 0 {
-            1
+            GameResult::Win(1)
+        } else if matches!(self.draw_rules.max_ply, Some(max_ply) if self.ply >= max_ply)
+            || matches!(self.draw_rules.repetition, Some(repetition) if self.repetitions() >= repetition)
+        {
+            GameResult::Draw
         } else {
-            -1
+            GameResult::Ongoing
+        }
+    }
+
+    /// Apply the action of the agent in place, then return the record to undo it
+    ///
+    /// This is same to `Env::step`, but without allocation. The state is not changed if the action is failed.
+    /// The action is rejected if it is not the turn of the agent, unless it is in the analysis mode.
+    pub fn apply(&mut self, agent_id: usize, action: &Action) -> Result<Undo, StepError> {
+        Env::apply(self, agent_id, action)
+    }
+
+    /// Revert the action applied by `apply`
+    ///
+    /// The undo records should be reverted in the reverse order of applying.
+    pub fn undo(&mut self, undo: Undo) {
+        Env::undo(self, undo)
+    }
+
+    /// Mirror the board upside down and swap the agents, so the agent 1 becomes the agent 0 heading to y = 8
+    ///
+    /// The draw rules are kept, but the history of the positions is not.
+    ///
+    /// For the actions except `RotateSection`, `step(state.flipped(), 1 - agent_id, action.flipped())` is same to the flipped `step(state, agent_id, action)`.
+    pub fn flipped(&self) -> Self {
+        let flip = |(x, y): Position| (x, 8 - y);
+
+        let mut state = State::from_fields(
+            [flip(self.players[1]), flip(self.players[0])],
+            // every channel is flipped by reversing y axis
+            [0, 1, 2, 3].map(|c| self.board[c].slice(s![.., ..;-1]).to_owned()),
+            [self.remaining_walls[1], self.remaining_walls[0]],
+        );
+        state.set_turn(1 - self.to_move, self.ply);
+        state.analysis = self.analysis;
+        state.draw_rules = self.draw_rules;
+
+        state
+    }
+
+    /// Mirror the board left and right, and the agents are kept
+    ///
+    /// The draw rules are kept, but the history of the positions is not.
+    ///
+    /// For the actions except `RotateSection`, `step(state.mirrored(), agent_id, action.mirrored())` is same to the mirrored `step(state, agent_id, action)`.
+    pub fn mirrored(&self) -> Self {
+        let mirror = |(x, y): Position| (8 - x, y);
+
+        let mut state = State::from_fields(
+            self.players.map(mirror),
+            // every channel is mirrored by reversing x axis
+            [0, 1, 2, 3].map(|c| self.board[c].slice(s![..;-1, ..]).to_owned()),
+            self.remaining_walls,
+        );
+        state.set_turn(self.to_move, self.ply);
+        state.analysis = self.analysis;
+        state.draw_rules = self.draw_rules;
+
+        state
+    }
+
+    /*
+     * The observation from the agent, with size (8, 9, 9). The (x, y) starts from top-left like the board.
+     * - 0: one-hot encoded position of the agent's pawn
+     * - 1: one-hot encoded position of the other pawn
+     * - 2: horizontal walls between (x, y) and (x, y + 1)
+     * - 3: vertical walls between (x, y) and (x + 1, y)
+     * - 4: middle points of horizontal walls, on the corner of (x, y), (x + 1, y), (x, y + 1) and (x + 1, y + 1)
+     * - 5: middle points of vertical walls, same to the channel 4
+     * - 6: the agent's remaining walls divided by 10, on every position
+     * - 7: the other agent's remaining walls divided by 10, on every position
+     *
+     * If perspective is set, the board is flipped upside down for the agent 1, so the agent always heads to y = 8.
+     */
+    pub const OBSERVATION_SHAPE: [usize; 3] = [8, 9, 9];
+
+    pub fn to_observation(&self, agent_id: usize, perspective: bool) -> Array3<f32> {
+        let opposite_id = (agent_id + 1) % 2;
+        let flip = perspective && agent_id == 1;
+
+        // flip the row of the cell, and the row between the cells
+        let cell_y = |y: usize| if flip { 8 - y } else { y };
+        let line_y = |y: usize| if flip { 7 - y } else { y };
+
+        let mut observation = Array3::zeros(State::OBSERVATION_SHAPE);
+
+        let (x, y) = i(self.players[agent_id]);
+        observation[[0, x, cell_y(y)]] = 1.0;
+
+        let (x, y) = i(self.players[opposite_id]);
+        observation[[1, x, cell_y(y)]] = 1.0;
+
+        for x in 0..9 {
+            for y in 0..8 {
+                observation[[2, x, line_y(y)]] = self.board[0][[x, y + 1]] as f32;
+                observation[[3, y, cell_y(x)]] = self.board[1][[y + 1, x]] as f32;
+            }
         }
+
+        for x in 0..8 {
+            for y in 0..8 {
+                observation[[4, x, line_y(y)]] = self.board[2][[x + 1, y + 1]] as f32;
+                observation[[5, x, line_y(y)]] = self.board[3][[x + 1, y + 1]] as f32;
+            }
+        }
+
+        observation
+            .index_axis_mut(Axis(0), 6)
+            .fill(self.remaining_walls[agent_id] as f32 / 10.0);
+        observation
+            .index_axis_mut(Axis(0), 7)
+            .fill(self.remaining_walls[opposite_id] as f32 / 10.0);
+
+        observation
     }
 
     pub fn display_with(&self, marker_board: Option<(&str, Array2<u8>)>) -> String {
@@ -227,7 +848,7 @@ impl State {
                     }
                 }
 
-                result += &right_intersection;
+                result += right_intersection;
                 result += "\n";
             }
         }
@@ -247,56 +868,230 @@ impl State {
             }
         }
 
-        result = result + right_intersection_bottom;
+        result += right_intersection_bottom;
 
         result
     }
 }
 
-impl BaseState for State {}
+/*
+ * The walls and pins of the 4x4 section, indexed by [x][y] from the top-left position of the section
+ * - horizontal: horizontal walls (size: (4, 5))
+ * - vertical: vertical walls (size: (5, 4))
+ * - pins: the middle points of horizontal and vertical walls (size: (5, 5))
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Section {
+    horizontal: [[bool; 5]; 4],
+    vertical: [[bool; 4]; 5],
+    pins: [[[bool; 5]; 5]; 2],
+}
 
-pub struct Env {}
+impl Section {
+    fn read<B: Board>(board: &B, pos: Position) -> Self {
+        let (px, py) = i(pos);
+        let mut section = Section::default();
 
-impl Env {
-    // check if now and new is attached and there is no wall between them
-    fn is_blocked_between(now: Position, new: Position, state: &State) -> bool {
-        (now.1 > 0 && up(now) == new && state.board[0][i(now)] == 1)
-            || (now.1 < 8 && down(now) == new && state.board[0][i(new)] == 1)
-            || (now.0 > 0 && left(now) == new && state.board[1][i(now)] == 1)
-            || (now.0 < 8 && right(now) == new && state.board[1][i(new)] == 1)
+        for x in 0..=4 {
+            for y in 0..=4 {
+                if x < 4 {
+                    section.horizontal[x][y] = board.wall(0, (px + x, py + y));
+                }
+
+                if y < 4 {
+                    section.vertical[x][y] = board.wall(1, (px + x, py + y));
+                }
+
+                section.pins[0][x][y] = board.wall(2, (px + x, py + y));
+                section.pins[1][x][y] = board.wall(3, (px + x, py + y));
+            }
+        }
+
+        section
+    }
+
+    fn write<B: Board>(&self, board: &mut B, pos: Position) {
+        let (px, py) = i(pos);
+
+        for x in 0..=4 {
+            for y in 0..=4 {
+                if x < 4 {
+                    board.set_wall(0, (px + x, py + y), self.horizontal[x][y]);
+                }
+
+                if y < 4 {
+                    board.set_wall(1, (px + x, py + y), self.vertical[x][y]);
+                }
+
+                board.set_wall(2, (px + x, py + y), self.pins[0][x][y]);
+                board.set_wall(3, (px + x, py + y), self.pins[1][x][y]);
+            }
+        }
+    }
+}
+
+/// The record of `State::apply` for reverting it by `State::undo`
+#[must_use]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Undo {
+    agent_id: usize,
+    action: Action,
+    // the agent to move and the ply before the action
+    turn: (usize, usize),
+    // the history of the positions forgotten by the wall action
+    history: Vec<u64>,
+    change: Change,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Change {
+    // the previous position of the pawn
+    Move(Position),
+    PlaceWall,
+    // the section before rotating
+    RotateSection(Section),
+}
+
+// The board which the rules are applied on, for sharing them between State and CompactState
+trait Board {
+    fn players(&self) -> &[Position; 2];
+
+    fn move_pawn(&mut self, agent_id: usize, pos: Position);
+
+    fn remaining_walls(&self) -> &[u8; 2];
+
+    fn set_remaining_walls(&mut self, agent_id: usize, walls: u8);
+
+    // if there is a wall or a pin on the position of the channel, same to State::board
+    fn wall(&self, channel: usize, pos: PositionIdx) -> bool;
+
+    fn set_wall(&mut self, channel: usize, pos: PositionIdx, value: bool);
+
+    // the agent to move and the ply
+    fn turn(&self) -> (usize, usize);
+
+    fn set_turn(&mut self, to_move: usize, ply: usize);
+
+    fn is_analysis(&self) -> bool;
+
+    // remember the position before the action for the repetition, and return the forgotten positions if it is a wall action
+    fn push_history(&mut self, before: u64, is_move: bool) -> Vec<u64>;
+
+    // revert push_history
+    fn pop_history(&mut self, is_move: bool, forgotten: Vec<u64>);
+
+    // the key of the position for the history, which is the zobrist hash
+    fn history_key(&self) -> u64;
+
+    // check if the pawn can arrive at the winning area, regardless of the other pawn
+    fn is_pawn_can_win(&self, agent_id: usize) -> bool;
+}
+
+impl Board for State {
+    #[inline]
+    fn players(&self) -> &[Position; 2] {
+        &self.players
+    }
+
+    #[inline]
+    fn move_pawn(&mut self, agent_id: usize, pos: Position) {
+        self.zobrist ^=
+            zobrist::player(agent_id, self.players[agent_id]) ^ zobrist::player(agent_id, pos);
+        self.players[agent_id] = pos;
+    }
+
+    #[inline]
+    fn remaining_walls(&self) -> &[u8; 2] {
+        &self.remaining_walls
+    }
+
+    #[inline]
+    fn set_remaining_walls(&mut self, agent_id: usize, walls: u8) {
+        self.zobrist ^= zobrist::remaining_walls(agent_id, self.remaining_walls[agent_id])
+            ^ zobrist::remaining_walls(agent_id, walls);
+        self.remaining_walls[agent_id] = walls;
+    }
+
+    #[inline]
+    fn wall(&self, channel: usize, pos: PositionIdx) -> bool {
+        self.board[channel][pos] == 1
+    }
+
+    #[inline]
+    fn set_wall(&mut self, channel: usize, pos: PositionIdx, value: bool) {
+        if self.wall(channel, pos) != value {
+            self.zobrist ^= zobrist::wall(channel, pos);
+            self.board[channel][pos] = value as u8;
+        }
+    }
+
+    #[inline]
+    fn turn(&self) -> (usize, usize) {
+        (self.to_move, self.ply)
+    }
+
+    #[inline]
+    fn set_turn(&mut self, to_move: usize, ply: usize) {
+        self.zobrist ^= zobrist::to_move(self.to_move) ^ zobrist::to_move(to_move);
+        self.to_move = to_move;
+        self.ply = ply;
+    }
+
+    #[inline]
+    fn is_analysis(&self) -> bool {
+        self.analysis
+    }
+
+    fn push_history(&mut self, before: u64, is_move: bool) -> Vec<u64> {
+        if is_move {
+            self.history.push(before);
+            Vec::new()
+        } else {
+            // the positions before the wall action cannot appear again
+            mem::take(&mut self.history)
+        }
+    }
+
+    fn pop_history(&mut self, is_move: bool, forgotten: Vec<u64>) {
+        if is_move {
+            self.history.pop();
+        } else {
+            self.history = forgotten;
+        }
     }
 
-    fn is_pawn_can_win(agent_id: usize, state: &State) -> bool {
+    #[inline]
+    fn history_key(&self) -> u64 {
+        self.zobrist
+    }
+
+    fn is_pawn_can_win(&self, agent_id: usize) -> bool {
         let mut queue = VecDeque::new();
 
         let win_y = if agent_id == 0 { 8 } else { 0 };
-        queue.push_back(state.players[agent_id]);
+        queue.push_back(self.players[agent_id]);
 
-        let mut visited = Array2::zeros([9, 9]);
+        let mut visited = [[false; 9]; 9];
 
         while let Some(pos) = queue.pop_front() {
             if pos.1 == win_y {
                 return true;
             }
 
-            if pos.1 > 0 && state.board[0][i(pos)] != 1 && visited[i(up(pos))] == 0 {
-                visited[i(up(pos))] = 1;
-                queue.push_back(up(pos));
-            }
-
-            if pos.1 < 8 && state.board[0][i(down(pos))] != 1 && visited[i(down(pos))] == 0 {
-                visited[i(down(pos))] = 1;
-                queue.push_back(down(pos));
-            }
+            let nexts = [
+                (pos.1 > 0 && !self.wall(0, i(pos))).then(|| up(pos)),
+                (pos.1 < 8 && !self.wall(0, i(down(pos)))).then(|| down(pos)),
+                (pos.0 > 0 && !self.wall(1, i(pos))).then(|| left(pos)),
+                (pos.0 < 8 && !self.wall(1, i(right(pos)))).then(|| right(pos)),
+            ];
 
-            if pos.0 > 0 && state.board[1][i(pos)] != 1 && visited[i(left(pos))] == 0 {
-                visited[i(left(pos))] = 1;
-                queue.push_back(left(pos));
-            }
+            for next in nexts.into_iter().flatten() {
+                let (x, y) = i(next);
 
-            if pos.0 < 8 && state.board[1][i(right(pos))] != 1 && visited[i(right(pos))] == 0 {
-                visited[i(right(pos))] = 1;
-                queue.push_back(right(pos));
+                if !visited[x][y] {
+                    visited[x][y] = true;
+                    queue.push_back(next);
+                }
             }
         }
 
@@ -304,260 +1099,724 @@ impl Env {
     }
 }
 
-impl BaseEnv<State, Action> for Env {
-    fn env_id() -> (String, i32) {
-        todo!()
+impl BaseState for State {}
+
+#[derive(Debug)]
+pub struct Env {}
+
+impl Env {
+    // check if now and new is attached and there is no wall between them
+    fn is_blocked_between<B: Board>(now: Position, new: Position, state: &B) -> bool {
+        (now.1 > 0 && up(now) == new && state.wall(0, i(now)))
+            || (now.1 < 8 && down(now) == new && state.wall(0, i(new)))
+            || (now.0 > 0 && left(now) == new && state.wall(1, i(now)))
+            || (now.0 < 8 && right(now) == new && state.wall(1, i(new)))
     }
 
-    fn initialize_state() -> State {
-        State::new()
+    fn is_pawns_can_win<B: Board>(state: &B) -> bool {
+        state.is_pawn_can_win(0) && state.is_pawn_can_win(1)
     }
 
-    fn step(state: State, agent_id: usize, action: Action) -> Result<State, String> {
-        match action.action_type() {
-            ActionType::Move => {
-                let opposite = state.players[(agent_id + 1) % 2];
-                let now = state.players[agent_id];
-                let new = action.position();
+    // check if the pawn of agent can move to new, including jumping over the other pawn
+    fn check_move<B: Board>(state: &B, agent_id: usize, action: &Action) -> Result<(), StepError> {
+        Env::check_move_from(state, agent_id, state.players()[agent_id], action)
+    }
 
-                if new.0 >= 9 || new.1 >= 9 {
-                    return Err!("Move: out of board");
-                }
+    // same to check_move, but the pawn of agent is assumed to be on now
+    fn check_move_from<B: Board>(
+        state: &B,
+        agent_id: usize,
+        now: Position,
+        action: &Action,
+    ) -> Result<(), StepError> {
+        let new = action.position();
+        let opposite = state.players()[(agent_id + 1) % 2];
+
+        if new.0 >= 9 || new.1 >= 9 {
+            return step_error!(OutOfBoard, agent_id, action);
+        }
 
-                if new == opposite {
-                    return Err!("Move: cannot overlap the other pawn. How about jumping over it?");
-                }
+        if new == opposite {
+            return step_error!(OverlapPawn, agent_id, action);
+        }
 
-                let diff = diff_pos(now, new);
-                if diff == 1 {
-                    if Env::is_blocked_between(now, new, &state) {
-                        return Err!("Move: the movement is blocked by wall.");
-                    }
-                } else if diff == 2 {
-                    if Env::is_blocked_between(now, opposite, &state)
-                        || Env::is_blocked_between(opposite, new, &state)
-                    {
-                        return Err!("Move: there is a wall between now pawn position, opposite pawn position, and new pawn position.");
-                    }
+        let diff = diff_pos(now, new);
+        if diff == 1 {
+            if Env::is_blocked_between(now, new, state) {
+                return step_error!(BlockedByWall, agent_id, action);
+            }
+        } else if diff == 2 {
+            if Env::is_blocked_between(now, opposite, state)
+                || Env::is_blocked_between(opposite, new, state)
+            {
+                return step_error!(BlockedByWall, agent_id, action);
+            }
 
-                    // check straight jump over condition
-                    if !is_mid_pos(now, opposite, new) {
-                        // check diagonal jump over condition
-
-                        if !((now.1 > 0
-                            && up(now) == opposite
-                            && (state.board[0][i(opposite)] == 1 || opposite.1 == 0)
-                            && ((now.0 > 0 && left(opposite) == new)
-                                || (now.0 < 8 && right(opposite) == new)))
-                            || (now.1 < 8
-                                && down(now) == opposite
-                                && (state.board[0][i(down(opposite))] == 1 || opposite.1 == 8)
-                                && ((now.0 > 0 && left(opposite) == new)
-                                    || (now.0 < 8 && right(opposite) == new)))
-                            || (now.0 > 0
-                                && left(now) == opposite
-                                && (state.board[1][i(opposite)] == 1 || opposite.0 == 0)
-                                && ((now.1 > 0 && up(opposite) == new)
-                                    || (now.1 < 8 && down(opposite) == new)))
-                            || (now.0 < 8
-                                && right(now) == opposite
-                                && (state.board[1][i(right(opposite))] == 1 || opposite.0 == 8)
-                                && ((now.1 > 0 && up(opposite) == new)
-                                    || (now.1 < 8 && down(opposite) == new))))
-                        {
-                            return Err!("Move: cannot jump straightly or diagonally.");
-                        }
-                    }
-                } else {
-                    return Err!("Move: should move one block, not zero or bigger than one.");
+            // check straight jump over condition
+            if !is_mid_pos(now, opposite, new) {
+                // check diagonal jump over condition
+
+                if !((now.1 > 0
+                    && up(now) == opposite
+                    && (state.wall(0, i(opposite)) || opposite.1 == 0)
+                    && ((now.0 > 0 && left(opposite) == new)
+                        || (now.0 < 8 && right(opposite) == new)))
+                    || (now.1 < 8
+                        && down(now) == opposite
+                        && (state.wall(0, i(down(opposite))) || opposite.1 == 8)
+                        && ((now.0 > 0 && left(opposite) == new)
+                            || (now.0 < 8 && right(opposite) == new)))
+                    || (now.0 > 0
+                        && left(now) == opposite
+                        && (state.wall(1, i(opposite)) || opposite.0 == 0)
+                        && ((now.1 > 0 && up(opposite) == new)
+                            || (now.1 < 8 && down(opposite) == new)))
+                    || (now.0 < 8
+                        && right(now) == opposite
+                        && (state.wall(1, i(right(opposite))) || opposite.0 == 8)
+                        && ((now.1 > 0 && up(opposite) == new)
+                            || (now.1 < 8 && down(opposite) == new))))
+                {
+                    return step_error!(IllegalJump, agent_id, action);
                 }
+            }
+        } else {
+            return step_error!(InvalidDistance, agent_id, action);
+        }
 
-                let players = if agent_id == 0 {
-                    [new, opposite]
-                } else {
-                    [opposite, new]
-                };
+        Ok(())
+    }
+
+    // check everything for placing horizontal wall except the path to win
+    fn check_place_wall_horizontally<B: Board>(
+        state: &B,
+        agent_id: usize,
+        action: &Action,
+    ) -> Result<(), StepError> {
+        let pos = action.position();
+
+        if state.remaining_walls()[agent_id] == 0 {
+            return step_error!(NoRemainingWalls, agent_id, action);
+        }
+
+        if pos.0 >= 8 || pos.1 == 0 || pos.1 >= 9 {
+            return step_error!(OutOfBoard, agent_id, action);
+        }
+
+        if state.wall(0, i(pos)) || state.wall(0, i(right(pos))) {
+            return step_error!(OverlapWall, agent_id, action);
+        }
+
+        if state.wall(3, i(right(pos))) {
+            return step_error!(Intersecting, agent_id, action);
+        }
+
+        Ok(())
+    }
+
+    // check everything for placing vertical wall except the path to win
+    fn check_place_wall_vertically<B: Board>(
+        state: &B,
+        agent_id: usize,
+        action: &Action,
+    ) -> Result<(), StepError> {
+        let pos = action.position();
+
+        if state.remaining_walls()[agent_id] == 0 {
+            return step_error!(NoRemainingWalls, agent_id, action);
+        }
+
+        if pos.0 == 0 || pos.0 >= 9 || pos.1 >= 8 {
+            return step_error!(OutOfBoard, agent_id, action);
+        }
+
+        if state.wall(1, i(pos)) || state.wall(1, i(down(pos))) {
+            return step_error!(OverlapWall, agent_id, action);
+        }
+
+        if state.wall(2, i(down(pos))) {
+            return step_error!(Intersecting, agent_id, action);
+        }
+
+        Ok(())
+    }
+
+    // check everything for rotating section except the path to win
+    fn check_rotate_section<B: Board>(
+        state: &B,
+        agent_id: usize,
+        action: &Action,
+    ) -> Result<(), StepError> {
+        let pos = action.position();
+
+        if state.remaining_walls()[agent_id] <= 1 {
+            return step_error!(NoRemainingWalls, agent_id, action);
+        }
+
+        if pos.0 >= 6 || pos.1 >= 6 {
+            return step_error!(OutOfBoard, agent_id, action);
+        }
+
+        Ok(())
+    }
 
-                let state = State { players, ..state };
+    fn place_wall_horizontally<B: Board>(state: &mut B, pos: Position) {
+        state.set_wall(0, i(pos), true);
+        state.set_wall(0, i(right(pos)), true);
+        state.set_wall(2, i(right(pos)), true);
+    }
+
+    fn place_wall_vertically<B: Board>(state: &mut B, pos: Position) {
+        state.set_wall(1, i(pos), true);
+        state.set_wall(1, i(down(pos)), true);
+        state.set_wall(3, i(down(pos)), true);
+    }
+
+    fn remove_wall_horizontally<B: Board>(state: &mut B, pos: Position) {
+        state.set_wall(0, i(pos), false);
+        state.set_wall(0, i(right(pos)), false);
+        state.set_wall(2, i(right(pos)), false);
+    }
+
+    fn remove_wall_vertically<B: Board>(state: &mut B, pos: Position) {
+        state.set_wall(1, i(pos), false);
+        state.set_wall(1, i(down(pos)), false);
+        state.set_wall(3, i(down(pos)), false);
+    }
 
-                Ok(state)
+    fn rotate_section<B: Board>(state: &mut B, pos: Position) {
+        let section = Section::read(state, pos);
+        let mut rotated = Section::default();
+
+        // horizontal -> vertial: make position to (x, y) => (4 - y, x)
+        for y in 0..=4 {
+            for x in 0..4 {
+                rotated.vertical[4 - y][x] = section.horizontal[x][y];
             }
-            ActionType::PlaceWallHorizontally => {
-                if state.remaining_walls[agent_id] == 0 {
-                    return Err!("PlaceWallHorizontally: there is no remaing wall for the agent.");
-                }
+        }
 
-                let pos = action.position();
+        // vertical -> horizontal: make positon to (x, y) => (3 - y, x)
+        for y in 0..4 {
+            for x in 0..=4 {
+                rotated.horizontal[3 - y][x] = section.vertical[x][y];
+            }
+        }
 
-                if pos.0 >= 8 || pos.1 == 0 || pos.1 >= 9 {
-                    return Err!("PlaceWallHorizontally: out of board");
-                }
+        // rotate to (x, y) => (4 - y, x) and remove intersecting pins
+        for y in 0..=4 {
+            for x in 0..=4 {
+                rotated.pins[0][4 - y][x] = section.pins[1][x][y];
+                rotated.pins[1][4 - y][x] = section.pins[0][x][y];
+            }
+        }
 
-                if state.board[0][i(pos)] == 1 || state.board[0][i(right(pos))] == 1 {
-                    return Err!("PlaceWallHorizontally: there is already horizontal wall.");
-                }
+        for j in 0..=4 {
+            rotated.pins[0][0][j] = false;
+            rotated.pins[0][4][j] = false;
 
-                if state.board[3][i(right(pos))] == 1 {
-                    return Err!(
-                        "PlaceWallHorizontally: cannot install horizontal wall intersecting."
-                    );
-                }
+            rotated.pins[1][j][0] = false;
+            rotated.pins[1][j][4] = false;
+        }
 
-                let mut state = state;
-                state.remaining_walls[agent_id] -= 1;
-                state.board[0][i(pos)] = 1;
-                state.board[0][i(right(pos))] = 1;
-                state.board[2][i(right(pos))] = 1;
+        rotated.write(state, pos);
 
-                if !(Env::is_pawn_can_win(agent_id, &state)
-                    && Env::is_pawn_can_win((agent_id + 1) % 2, &state))
-                {
-                    return Err!(
-                        "PlaceWallHorizontally: this can make for the other player not to win."
-                    );
-                }
+        // remove the edge walls and mid points
+        for i in 0..9 {
+            state.set_wall(0, (i, 0), false);
+            state.set_wall(0, (i, 9), false);
+            state.set_wall(1, (0, i), false);
+            state.set_wall(1, (9, i), false);
 
-                Ok(state)
+            state.set_wall(2, (i, 0), false);
+            state.set_wall(2, (i, 9), false);
+            state.set_wall(2, (0, i), false);
+            state.set_wall(2, (9, i), false);
+
+            state.set_wall(3, (i, 0), false);
+            state.set_wall(3, (i, 9), false);
+            state.set_wall(3, (0, i), false);
+            state.set_wall(3, (9, i), false);
+        }
+    }
+
+    // apply the action on the board in place, and return the record to undo it
+    fn apply<B: Board>(state: &mut B, agent_id: usize, action: &Action) -> Result<Undo, StepError> {
+        Env::apply_with(state, agent_id, action, None)
+    }
+
+    // same to apply, but the paths to win are checked with the cache if it is given
+    fn apply_with<B: Board>(
+        state: &mut B,
+        agent_id: usize,
+        action: &Action,
+        cache: Option<&ReachabilityCache>,
+    ) -> Result<Undo, StepError> {
+        let pos = action.position();
+        let turn = state.turn();
+        let before = state.history_key();
+
+        if agent_id != turn.0 && !state.is_analysis() {
+            return step_error!(OutOfTurn, agent_id, action);
+        }
+
+        let is_pawns_can_win = |state: &B| match cache {
+            Some(cache) => cache.check(state),
+            None => Env::is_pawns_can_win(state),
+        };
+
+        let change = match action.action_type() {
+            ActionType::Move => {
+                Env::check_move(state, agent_id, action)?;
+
+                let from = state.players()[agent_id];
+                state.move_pawn(agent_id, pos);
+
+                Change::Move(from)
             }
-            ActionType::PlaceWallVertically => {
-                if state.remaining_walls[agent_id] == 0 {
-                    return Err!("PlaceWallVertically: there is no remaing wall for the agent.");
-                }
+            ActionType::PlaceWallHorizontally => {
+                Env::check_place_wall_horizontally(state, agent_id, action)?;
 
-                let pos = action.position();
+                Env::place_wall_horizontally(state, pos);
 
-                if pos.0 == 0 || pos.0 >= 9 || pos.1 >= 8 {
-                    return Err!("PlaceWallVertically: out of board");
+                if !is_pawns_can_win(state) {
+                    Env::remove_wall_horizontally(state, pos);
+                    return step_error!(WouldTrapPawn, agent_id, action);
                 }
 
-                if state.board[1][i(pos)] == 1 || state.board[1][i(down(pos))] == 1 {
-                    return Err!("PlaceWallVertically: there is already vertical wall.");
-                }
+                state.set_remaining_walls(agent_id, state.remaining_walls()[agent_id] - 1);
 
-                if state.board[2][i(down(pos))] == 1 {
-                    return Err!("PlaceWallVertically: cannot install vertical wall intersecting.");
-                }
+                Change::PlaceWall
+            }
+            ActionType::PlaceWallVertically => {
+                Env::check_place_wall_vertically(state, agent_id, action)?;
 
-                let mut state = state;
-                state.remaining_walls[agent_id] -= 1;
-                state.board[1][i(pos)] = 1;
-                state.board[1][i(down(pos))] = 1;
-                state.board[3][i(down(pos))] = 1;
+                Env::place_wall_vertically(state, pos);
 
-                if !(Env::is_pawn_can_win(agent_id, &state)
-                    && Env::is_pawn_can_win((agent_id + 1) % 2, &state))
-                {
-                    return Err!(
-                        "PlaceWallVertically: this can make for the other player not to win."
-                    );
+                if !is_pawns_can_win(state) {
+                    Env::remove_wall_vertically(state, pos);
+                    return step_error!(WouldTrapPawn, agent_id, action);
                 }
 
-                Ok(state)
+                state.set_remaining_walls(agent_id, state.remaining_walls()[agent_id] - 1);
+
+                Change::PlaceWall
             }
             ActionType::RotateSection => {
-                if state.remaining_walls[agent_id] <= 1 {
-                    return Err!("RotationSection: there is no remainng wall for the agent.");
-                }
+                Env::check_rotate_section(state, agent_id, action)?;
 
-                let pos = action.position();
+                let section = Section::read(state, pos);
+                Env::rotate_section(state, pos);
 
-                if pos.0 >= 6 || pos.1 >= 6 {
-                    return Err!("RotationSection: out of board");
+                if !is_pawns_can_win(state) {
+                    section.write(state, pos);
+                    return step_error!(WouldTrapPawn, agent_id, action);
                 }
 
-                // horizontal -> vertial: make position to (x, y) => (4 - y, x)
-                let mut new_v = Array2::zeros([5, 4]);
+                state.set_remaining_walls(agent_id, state.remaining_walls()[agent_id] - 2);
 
-                for y in 0..=4 {
-                    for x in 0..4 {
-                        new_v[i((4 - y, x))] = state.board[0][i((pos.0 + x, pos.1 + y))];
-                    }
-                }
+                Change::RotateSection(section)
+            }
+        };
+
+        let history = state.push_history(before, matches!(change, Change::Move(_)));
+        state.set_turn((agent_id + 1) % 2, turn.1 + 1);
+
+        Ok(Undo {
+            agent_id,
+            action: action.clone(),
+            turn,
+            history,
+            change,
+        })
+    }
 
-                // vertical -> horizontal: make positon to (x, y) => (3 - y, x)
-                let mut new_h = Array2::zeros([4, 5]);
+    fn undo<B: Board>(state: &mut B, undo: Undo) {
+        let pos = undo.action.position();
+        state.set_turn(undo.turn.0, undo.turn.1);
+        state.pop_history(matches!(undo.change, Change::Move(_)), undo.history);
 
-                for y in 0..4 {
-                    for x in 0..=4 {
-                        new_h[i((3 - y, x))] = state.board[1][i((pos.0 + x, pos.1 + y))];
-                    }
+        match undo.change {
+            Change::Move(from) => state.move_pawn(undo.agent_id, from),
+            Change::PlaceWall => {
+                if undo.action.action_type() == ActionType::PlaceWallHorizontally {
+                    Env::remove_wall_horizontally(state, pos);
+                } else {
+                    Env::remove_wall_vertically(state, pos);
                 }
 
-                let mut state = state;
+                state
+                    .set_remaining_walls(undo.agent_id, state.remaining_walls()[undo.agent_id] + 1);
+            }
+            Change::RotateSection(section) => {
+                section.write(state, pos);
+                state
+                    .set_remaining_walls(undo.agent_id, state.remaining_walls()[undo.agent_id] + 2);
+            }
+        }
+    }
+
+    /// Collect every legal action of the agent on the state
+    ///
+    /// The order is moves, horizontal walls, vertical walls and rotations, each of them from top-left to bottom-right row by row.
+    pub fn legal_actions(state: &State, agent_id: usize) -> Vec<Action> {
+        Env::legal_actions_iter(state, agent_id).collect()
+    }
 
-                // apply them
-                for y in 0..=4 {
-                    for x in 0..4 {
-                        state.board[0][i((pos.0 + x, pos.1 + y))] = new_h[i((x, y))];
-                    }
-                }
+    /// Mask of the legal actions on the (action_type, x, y) shaped (4, 9, 9) action space
+    ///
+    /// The mask in standard layout is indexed by `Action::to_index` when it is flattened, so the walls are on their serialized positions.
+    pub fn legal_action_mask(state: &State, agent_id: usize) -> Array3<bool> {
+        let mut mask = Array3::from_elem([4, 9, 9], false);
 
-                for y in 0..4 {
-                    for x in 0..=4 {
-                        state.board[1][i((pos.0 + x, pos.1 + y))] = new_v[i((x, y))];
-                    }
-                }
+        let flat = mask.as_slice_mut().unwrap();
 
-                // remove the edge walls and mid points
-                for i in 0..9 {
-                    state.board[0][[i, 0]] = 0;
-                    state.board[0][[i, 9]] = 0;
-                    state.board[1][[0, i]] = 0;
-                    state.board[1][[9, i]] = 0;
-                }
+        // every legal action is on the 9x9 board
+        for action in Env::legal_actions_iter(state, agent_id) {
+            flat[action.to_index().unwrap()] = true;
+        }
 
-                // rotate to (x, y) => (4 - y, x) and remove intersecting pins
-                let mut new_h = state.board[2].clone();
-                let mut new_v = state.board[3].clone();
+        mask
+    }
 
-                for y in 0..=4 {
-                    for x in 0..=4 {
-                        new_h[i((pos.0 + 4 - y, pos.1 + x))] =
-                            state.board[3][i((pos.0 + x, pos.1 + y))];
-                        new_v[i((pos.0 + 4 - y, pos.1 + x))] =
-                            state.board[2][i((pos.0 + x, pos.1 + y))];
-                    }
+    /// Pair every legal action of the agent with its mirror on `state.mirrored()`
+    ///
+    /// The legal actions without a legal mirror(i.e. every `RotateSection`) are reported, so they can be excluded from the augmented data.
+    pub fn mirror_report(state: &State, agent_id: usize) -> MirrorReport {
+        let mirrored_mask = Env::legal_action_mask(&state.mirrored(), agent_id);
+        let mirrored_mask = mirrored_mask.as_slice().unwrap();
+
+        let mut report = MirrorReport {
+            mirrored: Vec::new(),
+            unmirrorable: Vec::new(),
+        };
+
+        for action in Env::legal_actions_iter(state, agent_id) {
+            match action.mirrored() {
+                Some(mirrored) if mirrored_mask[mirrored.to_index().unwrap()] => {
+                    report.mirrored.push((action, mirrored))
                 }
+                _ => report.unmirrorable.push(action),
+            }
+        }
 
-                for j in 0..=4 {
-                    new_h[i((pos.0, pos.1 + j))] = 0;
-                    new_h[i((pos.0 + 4, pos.1 + j))] = 0;
+        report
+    }
+
+    /// The shortest path of the pawn of agent to its winning area, excluding the current position
+    ///
+    /// Jumping over the other pawn is honored, while the other pawn is assumed to stay on its position. So `None` is returned if the other pawn blocks every path, even if the pawn can win after it moves.
+    pub fn shortest_path(state: &State, agent_id: usize) -> Option<Vec<Position>> {
+        Env::find_shortest_path(state, agent_id)
+    }
+
+    /// The number of moves of the shortest path from `Env::shortest_path`
+    pub fn shortest_path_len(state: &State, agent_id: usize) -> Option<usize> {
+        Env::find_shortest_path(state, agent_id).map(|path| path.len())
+    }
+
+    // BFS on the positions of the pawn, including jumps over the other pawn
+    fn find_shortest_path<B: Board>(state: &B, agent_id: usize) -> Option<Vec<Position>> {
+        let mut queue = VecDeque::new();
 
-                    new_v[i((pos.0 + j, pos.1))] = 0;
-                    new_v[i((pos.0 + j, pos.1 + 4))] = 0;
+        let win_y = if agent_id == 0 { 8 } else { 0 };
+        let start = state.players()[agent_id];
+        queue.push_back(start);
+
+        // the previous position on the path for every visited position
+        let mut prevs = [[None; 9]; 9];
+        prevs[start.0 as usize][start.1 as usize] = Some(start);
+
+        while let Some(pos) = queue.pop_front() {
+            if pos.1 == win_y {
+                let mut path = Vec::new();
+                let mut now = pos;
+
+                while now != start {
+                    path.push(now);
+                    now = prevs[now.0 as usize][now.1 as usize].unwrap();
                 }
+                path.reverse();
+
+                return Some(path);
+            }
 
-                state.board[2] = new_h;
-                state.board[3] = new_v;
-
-                // remove the edge walls and mid points
-                for i in 0..9 {
-                    state.board[0][[i, 0]] = 0;
-                    state.board[0][[i, 9]] = 0;
-                    state.board[1][[0, i]] = 0;
-                    state.board[1][[9, i]] = 0;
-
-                    state.board[2][[i, 0]] = 0;
-                    state.board[2][[i, 9]] = 0;
-                    state.board[2][[0, i]] = 0;
-                    state.board[2][[9, i]] = 0;
-
-                    state.board[3][[i, 0]] = 0;
-                    state.board[3][[i, 9]] = 0;
-                    state.board[3][[0, i]] = 0;
-                    state.board[3][[9, i]] = 0;
+            for (dx, dy) in PAWN_OFFSETS {
+                let next = (pos.0 as i8 + dx, pos.1 as i8 + dy);
+                if !(0..9).contains(&next.0) || !(0..9).contains(&next.1) {
+                    continue;
                 }
 
-                state.remaining_walls[agent_id] -= 2;
+                let next = (next.0 as u8, next.1 as u8);
+                let (x, y) = i(next);
 
-                if !(Env::is_pawn_can_win(agent_id, &state)
-                    && Env::is_pawn_can_win((agent_id + 1) % 2, &state))
+                if prevs[x][y].is_none()
+                    && Env::check_move_from(state, agent_id, pos, &Action::new(0, next)).is_ok()
                 {
-                    return Err!("RotationSection: this can make for the other player not to win.");
+                    prevs[x][y] = Some(pos);
+                    queue.push_back(next);
                 }
+            }
+        }
+
+        None
+    }
+
+    /// The (x, y) shaped (9, 9) map of the distances from every position to the winning area of agent
+    ///
+    /// Only the walls are considered, regardless of both pawns. `u8::MAX` is on the positions which cannot reach the winning area.
+    pub fn distance_map(state: &State, agent_id: usize) -> Array2<u8> {
+        let distances = Env::find_distances(state, agent_id);
+
+        Array2::from_shape_fn([9, 9], |(x, y)| distances[x][y])
+    }
+
+    // multi-source BFS from every position of the winning area
+    fn find_distances<B: Board>(state: &B, agent_id: usize) -> [[u8; 9]; 9] {
+        let mut queue = VecDeque::new();
+        let mut distances = [[u8::MAX; 9]; 9];
+
+        let win_y = if agent_id == 0 { 8 } else { 0 };
+        for x in 0..9 {
+            distances[x as usize][win_y as usize] = 0;
+            queue.push_back((x, win_y));
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            let distance = distances[pos.0 as usize][pos.1 as usize];
+
+            let nexts = [
+                (pos.1 > 0 && !state.wall(0, i(pos))).then(|| up(pos)),
+                (pos.1 < 8 && !state.wall(0, i(down(pos)))).then(|| down(pos)),
+                (pos.0 > 0 && !state.wall(1, i(pos))).then(|| left(pos)),
+                (pos.0 < 8 && !state.wall(1, i(right(pos)))).then(|| right(pos)),
+            ];
+
+            for next in nexts.into_iter().flatten() {
+                let (x, y) = i(next);
+
+                if distances[x][y] == u8::MAX {
+                    distances[x][y] = distance + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /// Iterate every legal action of the agent on the state lazily, in the same order with `legal_actions`
+    pub fn legal_actions_iter(state: &State, agent_id: usize) -> LegalActions<'_> {
+        let is_turn = agent_id == state.to_move || state.analysis;
+
+        LegalActions {
+            state,
+            agent_id,
+            scratch: None,
+            // there is no legal action out of the turn
+            cursor: if is_turn { 0 } else { LEGAL_ACTION_CANDIDATES },
+        }
+    }
+}
 
-                Ok(state)
+// the offsets of every position where the pawn can move to, including jumps
+const PAWN_OFFSETS: [(i8, i8); 12] = [
+    (0, -1),
+    (0, 1),
+    (-1, 0),
+    (1, 0),
+    (0, -2),
+    (0, 2),
+    (-2, 0),
+    (2, 0),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+/// The paths of both pawns to their winning areas, for checking if the pawns can still win after placing walls
+///
+/// The BFS is only run again if the new walls cut the cached path, or the pawn is moved. Either way, the result is same to the full BFS.
+#[derive(Debug, Clone)]
+pub struct ReachabilityCache {
+    // the positions from the pawn to the winning area, which are empty if the pawn cannot win
+    paths: [Vec<Position>; 2],
+}
+
+impl ReachabilityCache {
+    pub fn new(state: &State) -> Self {
+        ReachabilityCache::of(state)
+    }
+
+    /// Check if both pawns can arrive at their winning areas on the state
+    pub fn is_pawns_can_win(&self, state: &State) -> bool {
+        self.check(state)
+    }
+
+    fn of<B: Board>(state: &B) -> Self {
+        ReachabilityCache {
+            paths: [0, 1].map(|agent_id| ReachabilityCache::find_path(state, agent_id)),
+        }
+    }
+
+    // follow the decreasing distances from the pawn, which is one of the shortest paths
+    fn find_path<B: Board>(state: &B, agent_id: usize) -> Vec<Position> {
+        let distances = Env::find_distances(state, agent_id);
+        let mut pos = state.players()[agent_id];
+
+        if distances[pos.0 as usize][pos.1 as usize] == u8::MAX {
+            return Vec::new();
+        }
+
+        let mut path = vec![pos];
+        while distances[pos.0 as usize][pos.1 as usize] > 0 {
+            let distance = distances[pos.0 as usize][pos.1 as usize];
+
+            pos = PAWN_OFFSETS[..4]
+                .iter()
+                .map(|&(dx, dy)| ((pos.0 as i8 + dx) as u8, (pos.1 as i8 + dy) as u8))
+                .find(|&next| {
+                    next.0 < 9
+                        && next.1 < 9
+                        && distances[next.0 as usize][next.1 as usize] == distance - 1
+                        && !Env::is_blocked_between(pos, next, state)
+                })
+                .unwrap();
+            path.push(pos);
+        }
+
+        path
+    }
+
+    fn check<B: Board>(&self, state: &B) -> bool {
+        (0..2).all(|agent_id| {
+            let path = &self.paths[agent_id];
+
+            let is_path_alive = path.first() == Some(&state.players()[agent_id])
+                && path
+                    .windows(2)
+                    .all(|step| !Env::is_blocked_between(step[0], step[1], state));
+
+            is_path_alive || state.is_pawn_can_win(agent_id)
+        })
+    }
+}
+
+/// The result of `Env::mirror_report`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorReport {
+    /// The legal action and its mirror, which is legal on the mirrored state
+    pub mirrored: Vec<(Action, Action)>,
+    /// The legal action which has no legal mirror
+    pub unmirrorable: Vec<Action>,
+}
+
+/*
+ * The candidates of LegalActions, whose positions are not out of board
+ * - [0, 81): Move, (x, y) in [0, 9) x [0, 9)
+ * - [81, 145): PlaceWallHorizontally, (x, y) in [0, 8) x [1, 9)
+ * - [145, 209): PlaceWallVertically, (x, y) in [1, 9) x [0, 8)
+ * - [209, 245): RotateSection, (x, y) in [0, 6) x [0, 6)
+ */
+const LEGAL_ACTION_CANDIDATES: usize = 245;
+
+fn legal_action_candidate(index: usize) -> Action {
+    let (action_type, width, offset, index) = match index {
+        0..=80 => (0, 9, (0, 0), index),
+        81..=144 => (1, 8, (0, 1), index - 81),
+        145..=208 => (2, 8, (1, 0), index - 145),
+        _ => (3, 6, (0, 0), index - 209),
+    };
+
+    Action::new(
+        action_type,
+        (
+            (index % width) as u8 + offset.0,
+            (index / width) as u8 + offset.1,
+        ),
+    )
+}
+
+// the inverse of legal_action_candidate, which is None if the action is not one of the candidates
+fn legal_action_candidate_index(action: &Action) -> Option<usize> {
+    let (x, y) = i(action.position());
+
+    let (base, width, (x, y), (w, h)) = match action.action_type() {
+        ActionType::Move => (0, 9, (x, y), (9, 9)),
+        ActionType::PlaceWallHorizontally => (81, 8, (x, y.checked_sub(1)?), (8, 8)),
+        ActionType::PlaceWallVertically => (145, 8, (x.checked_sub(1)?, y), (8, 8)),
+        ActionType::RotateSection => (209, 6, (x, y), (6, 6)),
+    };
+
+    (x < w && y < h).then(|| base + y * width + x)
+}
+
+/// Lazy iterator over the legal actions, created by `Env::legal_actions_iter`
+///
+/// It checks the candidates directly on the board and only keeps one scratch state for the path checking, which is restored by `State::undo`. So it does not clone the state for each action.
+/// The paths to win are checked with `ReachabilityCache`, so the BFS is only run for the walls cutting them.
+pub struct LegalActions<'a> {
+    state: &'a State,
+    agent_id: usize,
+    scratch: Option<(State, ReachabilityCache)>,
+    cursor: usize,
+}
+
+impl LegalActions<'_> {
+    fn is_legal(&mut self, action: &Action) -> bool {
+        let state = self.state;
+
+        if action.action_type() == ActionType::Move {
+            return Env::check_move(state, self.agent_id, action).is_ok();
+        }
+
+        let (scratch, cache) = self
+            .scratch
+            .get_or_insert_with(|| (state.clone(), ReachabilityCache::new(state)));
+
+        match Env::apply_with(scratch, self.agent_id, action, Some(cache)) {
+            Ok(undo) => {
+                scratch.undo(undo);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Iterator for LegalActions<'_> {
+    type Item = Action;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.cursor < LEGAL_ACTION_CANDIDATES {
+            let action = legal_action_candidate(self.cursor);
+            self.cursor += 1;
+
+            if self.is_legal(&action) {
+                return Some(action);
             }
         }
+
+        None
+    }
+}
+
+impl BaseEnv<State, Action> for Env {
+    type Error = StepError;
+
+    // the version 2 rejects the actions out of turn
+    fn env_id() -> (String, i32) {
+        ("puoribor".to_string(), 2)
+    }
+
+    fn initialize_state() -> State {
+        State::new()
+    }
+
+    fn step(state: State, agent_id: usize, action: Action) -> Result<State, StepError> {
+        let mut state = state;
+        let _ = state.apply(agent_id, &action)?;
+
+        Ok(state)
     }
 }
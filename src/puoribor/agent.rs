@@ -0,0 +1,135 @@
+use rand::{
+    rngs::{StdRng, ThreadRng},
+    Rng, SeedableRng,
+};
+
+use super::{Action, ActionType, Env, State};
+use crate::utils::*;
+
+/// The player of the game, which chooses the action of the agent on each turn
+pub trait Agent {
+    /// Choose the action of the agent on the state, or `None` if the agent cannot choose any action
+    ///
+    /// The action may be illegal, which should be handled by the caller like forfeiting the game.
+    /// So is `None`, like when there is no legal action or it is not the turn of the agent.
+    fn act(&mut self, state: &State, agent_id: usize) -> Option<Action>;
+}
+
+/// The agent choosing one of the legal actions uniformly at random
+#[derive(Debug, Clone)]
+pub struct RandomAgent<R: Rng = ThreadRng> {
+    rng: R,
+}
+
+impl RandomAgent {
+    pub fn new() -> Self {
+        Self::with_rng(rand::thread_rng())
+    }
+}
+
+impl Default for RandomAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomAgent<StdRng> {
+    /// The agent choosing the same actions for the same seed and the same states
+    pub fn seeded(seed: u64) -> Self {
+        Self::with_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> RandomAgent<R> {
+    pub fn with_rng(rng: R) -> Self {
+        Self { rng }
+    }
+}
+
+impl<R: Rng> Agent for RandomAgent<R> {
+    fn act(&mut self, state: &State, agent_id: usize) -> Option<Action> {
+        let actions = Env::legal_actions(state, agent_id);
+
+        if actions.is_empty() {
+            None
+        } else {
+            Some(actions[self.rng.gen_range(0..actions.len())].clone())
+        }
+    }
+}
+
+/// The agent moving along the shortest path, but placing a wall if the other agent is closer to win
+///
+/// The wall is chosen by the most increase of the path of the other agent, subtracted by the increase of its own path.
+/// If no wall helps, it moves anyway. The ties are broken by the order of `Env::legal_actions`, so it is deterministic.
+#[derive(Debug, Clone, Default)]
+pub struct GreedyAgent {}
+
+impl GreedyAgent {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    // the distance of the pawn to its winning area by walls only, ignoring the other pawn
+    fn distance(state: &State, agent_id: usize) -> isize {
+        Env::distance_map(state, agent_id)[i(state.players[agent_id])] as isize
+    }
+
+    fn best_wall(state: &State, agent_id: usize) -> Option<Action> {
+        let opposite_id = (agent_id + 1) % 2;
+        let distances = [
+            GreedyAgent::distance(state, agent_id),
+            GreedyAgent::distance(state, opposite_id),
+        ];
+
+        let mut scratch = state.clone();
+        let mut best = None;
+        let mut best_gain = 0;
+
+        for action in Env::legal_actions_iter(state, agent_id) {
+            if !matches!(
+                action.action_type(),
+                ActionType::PlaceWallHorizontally | ActionType::PlaceWallVertically
+            ) {
+                continue;
+            }
+
+            let undo = scratch.apply(agent_id, &action).unwrap();
+            let gain = (GreedyAgent::distance(&scratch, opposite_id) - distances[1])
+                - (GreedyAgent::distance(&scratch, agent_id) - distances[0]);
+            scratch.undo(undo);
+
+            if gain > best_gain {
+                best = Some(action);
+                best_gain = gain;
+            }
+        }
+
+        best
+    }
+}
+
+impl Agent for GreedyAgent {
+    fn act(&mut self, state: &State, agent_id: usize) -> Option<Action> {
+        let opposite_id = (agent_id + 1) % 2;
+
+        // the agent to move wins the race on the same distance
+        let path = Env::shortest_path(state, agent_id);
+        let is_behind = match (&path, Env::shortest_path_len(state, opposite_id)) {
+            (Some(path), Some(len)) => len < path.len(),
+            _ => true,
+        };
+
+        if is_behind && state.remaining_walls[agent_id] > 0 {
+            if let Some(action) = GreedyAgent::best_wall(state, agent_id) {
+                return Some(action);
+            }
+        }
+
+        match path {
+            Some(path) if !path.is_empty() => Some(Action::new(0, path[0])),
+            // the other pawn blocks every path, so any legal move is done
+            _ => Env::legal_actions_iter(state, agent_id).next(),
+        }
+    }
+}
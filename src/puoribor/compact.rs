@@ -0,0 +1,335 @@
+use std::fmt;
+
+use super::{
+    legal_action_candidate, Action, Board, Env, GameResult, ReachabilityCache, State, StepError,
+    Undo, LEGAL_ACTION_CANDIDATES,
+};
+use crate::{envs::*, utils::*};
+
+/*
+ * Pouoribor's State on bitboards, which is same to State but does not allocate on heap
+ * - players, remaining_walls, to_move, ply, analysis: same to State
+ * - horizontal: the rows of State::board[0], whose bit x of row y is (x, y) (size: 10 rows of 9 bits)
+ * - vertical: the rows of State::board[1] (size: 9 rows of 10 bits)
+ * - pins: the rows of State::board[2] and State::board[3] (size: 10 rows of 10 bits)
+ *
+ * The draw rules and the history of State are not kept, like the position string.
+ */
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct CompactState {
+    pub players: [Position; 2],
+    pub horizontal: [u16; 10],
+    pub vertical: [u16; 9],
+    pub pins: [[u16; 10]; 2],
+    pub remaining_walls: [u8; 2],
+    to_move: u8,
+    ply: usize,
+    analysis: bool,
+}
+
+// the bits of the cells on a row
+const ROW: u16 = 0b1_1111_1111;
+
+impl Default for CompactState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompactState {
+    pub fn new() -> Self {
+        Self {
+            players: [(4, 0), (4, 8)],
+            horizontal: [0; 10],
+            vertical: [0; 9],
+            pins: [[0; 10]; 2],
+            remaining_walls: [10, 10],
+            to_move: 0,
+            ply: 0,
+            analysis: false,
+        }
+    }
+
+    /// The compact state of the position of the state
+    ///
+    /// Like the position string, the draw rules and the history of the positions are not kept, so the compact state is never a draw.
+    pub fn from_position(state: &State) -> Self {
+        let mut compact = CompactState {
+            players: state.players,
+            horizontal: [0; 10],
+            vertical: [0; 9],
+            pins: [[0; 10]; 2],
+            remaining_walls: state.remaining_walls,
+            to_move: state.to_move() as u8,
+            ply: state.ply(),
+            analysis: state.is_analysis(),
+        };
+
+        for (channel, board) in state.board.iter().enumerate() {
+            for ((x, y), &value) in board.indexed_iter() {
+                compact.set_wall(channel, (x, y), value == 1);
+            }
+        }
+
+        compact
+    }
+
+    /// The state of the position, which has no draw rules and no history
+    pub fn to_position(&self) -> State {
+        let mut state = State::new();
+
+        for agent_id in 0..2 {
+            state.move_pawn(agent_id, self.players[agent_id]);
+            state.set_remaining_walls(agent_id, self.remaining_walls[agent_id]);
+        }
+
+        state.set_turn(self.to_move(), self.ply());
+        state.set_analysis(self.analysis);
+
+        for channel in 0..4 {
+            let (width, height) = state.board[channel].dim();
+
+            for x in 0..width {
+                for y in 0..height {
+                    state.set_wall(channel, (x, y), self.wall(channel, (x, y)));
+                }
+            }
+        }
+
+        state
+    }
+
+    /// Same to `State::to_move`
+    #[inline]
+    pub fn to_move(&self) -> usize {
+        self.to_move as usize
+    }
+
+    /// Same to `State::ply`
+    #[inline]
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Same to `State::set_analysis`
+    pub fn set_analysis(&mut self, analysis: bool) {
+        self.analysis = analysis;
+    }
+
+    /// Same to `State::is_win`, but there is no draw because the history is not tracked
+    pub fn is_win(&self) -> GameResult {
+        if self.players[0].1 == 8 {
+            GameResult::Win(0)
+        } else if self.players[1].1 == 0 {
+            GameResult::Win(1)
+        } else {
+            GameResult::Ongoing
+        }
+    }
+
+    /// Same to `State::apply`
+    pub fn apply(&mut self, agent_id: usize, action: &Action) -> Result<Undo, StepError> {
+        Env::apply(self, agent_id, action)
+    }
+
+    /// Same to `State::undo`
+    pub fn undo(&mut self, undo: Undo) {
+        Env::undo(self, undo)
+    }
+
+    /// Same to `Env::legal_actions`
+    pub fn legal_actions(&self, agent_id: usize) -> Vec<Action> {
+        let cache = ReachabilityCache::of(self);
+
+        (0..LEGAL_ACTION_CANDIDATES)
+            .map(legal_action_candidate)
+            .filter(|action| {
+                let mut next = *self;
+                Env::apply_with(&mut next, agent_id, action, Some(&cache)).is_ok()
+            })
+            .collect()
+    }
+
+    #[inline]
+    fn row(&self, channel: usize, y: usize) -> &u16 {
+        match channel {
+            0 => &self.horizontal[y],
+            1 => &self.vertical[y],
+            _ => &self.pins[channel - 2][y],
+        }
+    }
+
+    #[inline]
+    fn row_mut(&mut self, channel: usize, y: usize) -> &mut u16 {
+        match channel {
+            0 => &mut self.horizontal[y],
+            1 => &mut self.vertical[y],
+            _ => &mut self.pins[channel - 2][y],
+        }
+    }
+}
+
+impl Board for CompactState {
+    #[inline]
+    fn players(&self) -> &[Position; 2] {
+        &self.players
+    }
+
+    #[inline]
+    fn move_pawn(&mut self, agent_id: usize, pos: Position) {
+        self.players[agent_id] = pos;
+    }
+
+    #[inline]
+    fn remaining_walls(&self) -> &[u8; 2] {
+        &self.remaining_walls
+    }
+
+    #[inline]
+    fn set_remaining_walls(&mut self, agent_id: usize, walls: u8) {
+        self.remaining_walls[agent_id] = walls;
+    }
+
+    #[inline]
+    fn wall(&self, channel: usize, pos: PositionIdx) -> bool {
+        (self.row(channel, pos.1) >> pos.0) & 1 == 1
+    }
+
+    #[inline]
+    fn set_wall(&mut self, channel: usize, pos: PositionIdx, value: bool) {
+        let row = self.row_mut(channel, pos.1);
+
+        if value {
+            *row |= 1 << pos.0;
+        } else {
+            *row &= !(1 << pos.0);
+        }
+    }
+
+    #[inline]
+    fn turn(&self) -> (usize, usize) {
+        (self.to_move(), self.ply())
+    }
+
+    #[inline]
+    fn set_turn(&mut self, to_move: usize, ply: usize) {
+        self.to_move = to_move as u8;
+        self.ply = ply;
+    }
+
+    #[inline]
+    fn is_analysis(&self) -> bool {
+        self.analysis
+    }
+
+    // the history is not tracked without allocation
+    #[inline]
+    fn push_history(&mut self, _before: u64, _is_move: bool) -> Vec<u64> {
+        Vec::new()
+    }
+
+    #[inline]
+    fn pop_history(&mut self, _is_move: bool, _forgotten: Vec<u64>) {}
+
+    #[inline]
+    fn history_key(&self) -> u64 {
+        0
+    }
+
+    // flood fill the reachable cells row by row until it is not changed
+    fn is_pawn_can_win(&self, agent_id: usize) -> bool {
+        let win_y = if agent_id == 0 { 8 } else { 0 };
+        let (x, y) = i(self.players[agent_id]);
+
+        let mut reached = [0u16; 9];
+        reached[y] = 1 << x;
+
+        loop {
+            let mut changed = false;
+
+            // sweep down and up, so the reached cells are spread to both directions on each loop
+            for y in (0..9).chain((0..9).rev()) {
+                // spread to left and right, where the vertical wall of (x, y) is on the left side of (x, y)
+                let mut row = reached[y];
+                loop {
+                    let spread = row
+                        | ((row & !self.vertical[y]) >> 1)
+                        | ((row << 1) & !self.vertical[y] & ROW);
+
+                    if spread == row {
+                        break;
+                    }
+
+                    row = spread;
+                }
+
+                if row != reached[y] {
+                    reached[y] = row;
+                    changed = true;
+                }
+
+                // spread to up and down, where the horizontal wall of (x, y) is on the top side of (x, y)
+                if y > 0 {
+                    let up = row & !self.horizontal[y] & !reached[y - 1];
+
+                    if up != 0 {
+                        reached[y - 1] |= up;
+                        changed = true;
+                    }
+                }
+
+                if y < 8 {
+                    let down = row & !self.horizontal[y + 1] & !reached[y + 1];
+
+                    if down != 0 {
+                        reached[y + 1] |= down;
+                        changed = true;
+                    }
+                }
+            }
+
+            if reached[win_y] != 0 {
+                return true;
+            }
+
+            if !changed {
+                return false;
+            }
+        }
+    }
+}
+
+impl fmt::Display for CompactState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_position().fmt(f)
+    }
+}
+
+impl BaseState for CompactState {}
+
+/// The environment same to `Env`, but on `CompactState`
+#[derive(Debug)]
+pub struct CompactEnv {}
+
+impl BaseEnv<CompactState, Action> for CompactEnv {
+    type Error = StepError;
+
+    fn env_id() -> (String, i32) {
+        Env::env_id()
+    }
+
+    fn initialize_state() -> CompactState {
+        CompactState::new()
+    }
+
+    fn step(
+        state: CompactState,
+        agent_id: usize,
+        action: Action,
+    ) -> Result<CompactState, StepError> {
+        let mut state = state;
+        let _ = state.apply(agent_id, &action)?;
+
+        Ok(state)
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+};
+
+use super::{
+    legal_action_candidate, legal_action_candidate_index, DrawRules, Env, GameRecord, GameResult,
+    State, LEGAL_ACTION_CANDIDATES,
+};
+use crate::envs::BaseEnv;
+
+/*
+ * The binary log of GameRecords, which is the header and the records in a row
+ * - header: b"PRBR", the version in 1 byte and the environment id like "puoribor-v2" in u16 length and bytes
+ * - record: the length of the payload in u32, the payload and CRC-32 of the payload in u32
+ *
+ * The payload of a record, whose numbers are in little endian
+ * - initial state: 0 for the initial state of the game, or 1 and the position string in u16 length and bytes
+ * - result: the winner, 2 for a draw, or 0xff if the game is not over
+ * - draw rules: the max ply in u32 and the repetition in u8, which are u32::MAX and 0 if they are disabled
+ * - plies: the number of the actions in u32
+ * - actions: 1 byte for each action, which is the index of the candidates of the legal actions
+ * - agents: the agents of the actions, 1 bit for each action from the lowest bit of the first byte
+ * - metadata: the number of the entries in u16, and each key and value in u16 length and bytes
+ */
+const MAGIC: &[u8; 4] = b"PRBR";
+
+/// The version of the binary log, which is written on the header
+pub const LOG_VERSION: u8 = 1;
+
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    /// The header does not start with the magic bytes
+    BadMagic,
+    /// The version of the log is not supported by this reader
+    UnsupportedVersion(u8),
+    /// The log is for another environment or rule version
+    IncompatibleEnv(String),
+    /// The payload of the record is different from its checksum
+    ChecksumMismatch {
+        expected: u32,
+        actual: u32,
+    },
+    /// The payload of the record cannot be decoded
+    Corrupted(String),
+    /// The record cannot be written in the log
+    Unencodable(String),
+}
+
+impl fmt::Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogError::Io(err) => write!(f, "io error: {}", err),
+            LogError::BadMagic => write!(f, "this is not a game log."),
+            LogError::UnsupportedVersion(version) => write!(
+                f,
+                "version {} is not supported, which should be {}.",
+                version, LOG_VERSION
+            ),
+            LogError::IncompatibleEnv(env) => write!(
+                f,
+                "the log is for {}, which is not compatible with {}.",
+                env,
+                Env::id()
+            ),
+            LogError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "the checksum is {:08x}, but the record has {:08x}.",
+                expected, actual
+            ),
+            LogError::Corrupted(reason) => write!(f, "corrupted record: {}", reason),
+            LogError::Unencodable(reason) => write!(f, "cannot write the record: {}", reason),
+        }
+    }
+}
+
+impl Error for LogError {}
+
+impl From<io::Error> for LogError {
+    fn from(err: io::Error) -> Self {
+        LogError::Io(err)
+    }
+}
+
+/// Writer of the binary log, which writes the header on creation and each record on `write`
+pub struct GameLogWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> GameLogWriter<W> {
+    pub fn new(mut writer: W) -> Result<Self, LogError> {
+        let env = Env::id();
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[LOG_VERSION])?;
+        writer.write_all(&len_u16(env.len())?.to_le_bytes())?;
+        writer.write_all(env.as_bytes())?;
+
+        Ok(Self { writer })
+    }
+
+    /// Write the record, which should be of the environment of the log
+    pub fn write(&mut self, record: &GameRecord) -> Result<(), LogError> {
+        if !Env::is_compatible(&record.env) {
+            return Err(LogError::Unencodable(format!("environment {}", record.env)));
+        }
+
+        let payload = encode(record)?;
+
+        self.writer
+            .write_all(&(payload.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&payload)?;
+        self.writer.write_all(&crc32(&payload).to_le_bytes())?;
+
+        Ok(())
+    }
+
+    /// Flush and return the inner writer
+    pub fn finish(mut self) -> Result<W, LogError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reader of the binary log, which iterates the records until the end of the log
+pub struct GameLogReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> GameLogReader<R> {
+    pub fn new(mut reader: R) -> Result<Self, LogError> {
+        let mut header = [0; 5];
+        reader.read_exact(&mut header)?;
+
+        if &header[..4] != MAGIC {
+            return Err(LogError::BadMagic);
+        }
+        if header[4] != LOG_VERSION {
+            return Err(LogError::UnsupportedVersion(header[4]));
+        }
+
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        let mut env = vec![0; u16::from_le_bytes(len) as usize];
+        reader.read_exact(&mut env)?;
+
+        let env =
+            String::from_utf8(env).map_err(|_| LogError::Corrupted("invalid utf-8".to_string()))?;
+        if !Env::is_compatible(&env) {
+            return Err(LogError::IncompatibleEnv(env));
+        }
+
+        Ok(Self { reader })
+    }
+
+    fn read_record(&mut self) -> Result<Option<GameRecord>, LogError> {
+        let mut len = [0; 4];
+
+        // the end of the log is only allowed between the records
+        match self.reader.read(&mut len[..1])? {
+            0 => return Ok(None),
+            _ => self.reader.read_exact(&mut len[1..])?,
+        }
+
+        // read it without allocating the length first, which can be broken
+        let len = u32::from_le_bytes(len) as usize;
+        let mut payload = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut payload)?;
+
+        if payload.len() != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        let mut checksum = [0; 4];
+        self.reader.read_exact(&mut checksum)?;
+
+        let (expected, actual) = (u32::from_le_bytes(checksum), crc32(&payload));
+        if expected != actual {
+            return Err(LogError::ChecksumMismatch { expected, actual });
+        }
+
+        decode(&payload).map(Some)
+    }
+}
+
+impl<R: Read> Iterator for GameLogReader<R> {
+    type Item = Result<GameRecord, LogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+fn encode(record: &GameRecord) -> Result<Vec<u8>, LogError> {
+    let mut payload = Vec::with_capacity(16 + record.actions.len() * 9 / 8);
+
+    if record.initial_state == State::new() && record.initial_state.ply() == 0 {
+        payload.push(0);
+    } else {
+        payload.push(1);
+        write_str(&mut payload, &record.initial_state.to_position_string())?;
+    }
+
+    payload.push(match record.result {
+        GameResult::Win(winner) if winner < 2 => winner as u8,
+        GameResult::Win(winner) => return Err(LogError::Unencodable(format!("winner {}", winner))),
+        GameResult::Draw => 2,
+        GameResult::Ongoing => 0xff,
+    });
+
+    let max_ply = match record.draw_rules.max_ply {
+        Some(max_ply) => u32::try_from(max_ply)
+            .ok()
+            .filter(|&max_ply| max_ply != u32::MAX)
+            .ok_or_else(|| LogError::Unencodable(format!("max ply {}", max_ply)))?,
+        None => u32::MAX,
+    };
+    let repetition = match record.draw_rules.repetition {
+        Some(repetition) => u8::try_from(repetition)
+            .ok()
+            .filter(|&repetition| repetition != 0)
+            .ok_or_else(|| LogError::Unencodable(format!("repetition {}", repetition)))?,
+        None => 0,
+    };
+    payload.extend_from_slice(&max_ply.to_le_bytes());
+    payload.push(repetition);
+
+    payload.extend_from_slice(&(record.actions.len() as u32).to_le_bytes());
+
+    for (_, action) in &record.actions {
+        let code = legal_action_candidate_index(action)
+            .ok_or_else(|| LogError::Unencodable(format!("action {:?}", action)))?;
+        payload.push(code as u8);
+    }
+
+    let mut agents = vec![0u8; agent_bytes(record.actions.len())];
+    for (ply, &(agent_id, _)) in record.actions.iter().enumerate() {
+        match agent_id {
+            0 => {}
+            1 => agents[ply / 8] |= 1 << (ply % 8),
+            _ => return Err(LogError::Unencodable(format!("agent {}", agent_id))),
+        }
+    }
+    payload.extend_from_slice(&agents);
+
+    payload.extend_from_slice(&len_u16(record.metadata.len())?.to_le_bytes());
+    for (key, value) in &record.metadata {
+        write_str(&mut payload, key)?;
+        write_str(&mut payload, value)?;
+    }
+
+    Ok(payload)
+}
+
+fn decode(payload: &[u8]) -> Result<GameRecord, LogError> {
+    let mut bytes = Bytes(payload);
+
+    let initial_state = match bytes.take(1)?[0] {
+        0 => State::new(),
+        1 => State::from_position_string(&bytes.take_str()?)
+            .map_err(|err| LogError::Corrupted(err.to_string()))?,
+        flag => {
+            return Err(LogError::Corrupted(format!(
+                "unknown initial state {}",
+                flag
+            )))
+        }
+    };
+    let mut record = GameRecord::new(initial_state);
+
+    record.result = match bytes.take(1)?[0] {
+        0xff => GameResult::Ongoing,
+        2 => GameResult::Draw,
+        winner @ 0..=1 => GameResult::Win(winner as usize),
+        winner => return Err(LogError::Corrupted(format!("unknown winner {}", winner))),
+    };
+
+    let max_ply = u32::from_le_bytes(bytes.take(4)?.try_into().unwrap());
+    let repetition = bytes.take(1)?[0];
+    record.draw_rules = DrawRules {
+        max_ply: (max_ply != u32::MAX).then_some(max_ply as usize),
+        repetition: (repetition != 0).then_some(repetition as usize),
+    };
+
+    let plies = u32::from_le_bytes(bytes.take(4)?.try_into().unwrap()) as usize;
+    let codes = bytes.take(plies)?;
+    let agents = bytes.take(agent_bytes(plies))?;
+
+    for (ply, &code) in codes.iter().enumerate() {
+        if code as usize >= LEGAL_ACTION_CANDIDATES {
+            return Err(LogError::Corrupted(format!("unknown action {}", code)));
+        }
+
+        let agent_id = (agents[ply / 8] >> (ply % 8) & 1) as usize;
+        record.push(agent_id, legal_action_candidate(code as usize));
+    }
+
+    let entries = u16::from_le_bytes(bytes.take(2)?.try_into().unwrap());
+    for _ in 0..entries {
+        let key = bytes.take_str()?;
+        record.metadata.insert(key, bytes.take_str()?);
+    }
+
+    if !bytes.0.is_empty() {
+        return Err(LogError::Corrupted("trailing bytes".to_string()));
+    }
+
+    Ok(record)
+}
+
+// the bytes for the agents of the plies, 1 bit for each
+fn agent_bytes(plies: usize) -> usize {
+    (plies + 7) >> 3
+}
+
+fn len_u16(len: usize) -> Result<u16, LogError> {
+    u16::try_from(len).map_err(|_| LogError::Unencodable(format!("length {}", len)))
+}
+
+fn write_str(payload: &mut Vec<u8>, s: &str) -> Result<(), LogError> {
+    payload.extend_from_slice(&len_u16(s.len())?.to_le_bytes());
+    payload.extend_from_slice(s.as_bytes());
+
+    Ok(())
+}
+
+// the cursor on the payload
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], LogError> {
+        if self.0.len() < len {
+            return Err(LogError::Corrupted("unexpected end of record".to_string()));
+        }
+
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Ok(taken)
+    }
+
+    fn take_str(&mut self) -> Result<String, LogError> {
+        let len = u16::from_le_bytes(self.take(2)?.try_into().unwrap());
+
+        String::from_utf8(self.take(len as usize)?.to_vec())
+            .map_err(|_| LogError::Corrupted("invalid utf-8".to_string()))
+    }
+}
+
+// CRC-32 of IEEE 802.3, same to zlib
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+
+    !crc
+}
@@ -0,0 +1,458 @@
+use std::{
+    fmt, mem,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    legal_action_candidate, search, Action, Agent, Env, GameResult, State, LEGAL_ACTION_CANDIDATES,
+};
+
+/// The rule to select the child on the tree
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Selection {
+    /// UCB1 on the mean value, where the unvisited children are selected first
+    Uct { exploration: f32 },
+    /// The prior weighted exploration of AlphaZero, where the unvisited children have zero value
+    Puct { exploration: f32 },
+}
+
+/// The policy to play from the new leaf, for the value of the leaf
+///
+/// It returns `None` if there is no legal action, which loses the rollout like `AlphaBeta`.
+pub type RolloutPolicy = fn(&State, &mut StdRng) -> Option<Action>;
+
+/// The way to get the value of the new leaf without `PriorValue`
+#[derive(Debug, Clone, Copy)]
+pub enum Rollout {
+    /// Score the leaf by `search::evaluate` without playing
+    Evaluation,
+    /// Play the uniformly random legal actions up to the plies, then score by `search::evaluate` if it is not over
+    Random { max_plies: usize },
+    /// Play the actions of the policy up to the plies, then score by `search::evaluate` if it is not over
+    Policy {
+        policy: RolloutPolicy,
+        max_plies: usize,
+    },
+}
+
+/// The external evaluation of the new leaf, like a neural network
+pub trait PriorValue {
+    /// The prior probabilities of the legal actions in the same order, and the value for the agent to move in [-1, 1]
+    fn prior_value(&mut self, state: &State, actions: &[Action]) -> (Vec<f32>, f32);
+}
+
+impl<F: FnMut(&State, &[Action]) -> (Vec<f32>, f32)> PriorValue for F {
+    fn prior_value(&mut self, state: &State, actions: &[Action]) -> (Vec<f32>, f32) {
+        self(state, actions)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MctsConfig {
+    /// The number of the simulations for each search
+    pub simulations: usize,
+    /// The search stops at the time even before the simulations, but it runs at least one simulation
+    pub time: Option<Duration>,
+    pub selection: Selection,
+    /// The rollout, which is not used if there is `PriorValue`
+    pub rollout: Rollout,
+    /// The seed of the random number generator, so the searches are same for the same seed
+    pub seed: u64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            simulations: 1000,
+            time: None,
+            selection: Selection::Uct { exploration: 1.4 },
+            rollout: Rollout::Evaluation,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsResult {
+    /// The most visited action
+    pub best_action: Action,
+    /// The mean value of the root for the agent to move
+    pub value: f32,
+    /// The visits of every legal action on the root, which is the target of the policy
+    pub visits: Vec<(Action, u32)>,
+    /// The simulations on the tree, including the reused ones
+    pub simulations: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    // the action to this node and the agent who did it, which are none for the root
+    action: Option<Action>,
+    agent_id: usize,
+    children: Vec<usize>,
+    prior: f32,
+    visits: u32,
+    // the sum of the values for the agent who did the action
+    value: f32,
+    // the zobrist hash of the state, for finding it on reusing the tree
+    zobrist: u64,
+    is_expanded: bool,
+}
+
+impl Node {
+    fn new(action: Option<Action>, agent_id: usize, prior: f32) -> Self {
+        Self {
+            action,
+            agent_id,
+            children: Vec::new(),
+            prior,
+            visits: 0,
+            value: 0.0,
+            zobrist: 0,
+            is_expanded: false,
+        }
+    }
+
+    fn mean(&self) -> f32 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.value / self.visits as f32
+        }
+    }
+}
+
+/// Monte Carlo tree search with UCT or PUCT
+///
+/// The tree is kept between the searches, and reused if the next state is on the tree within 2 plies like the turn of the other agent.
+pub struct Mcts {
+    config: MctsConfig,
+    prior_value: Option<Box<dyn PriorValue + Send>>,
+    rng: StdRng,
+    // the nodes of the tree, where the first one is the root
+    nodes: Vec<Node>,
+    root_state: Option<State>,
+}
+
+impl fmt::Debug for Mcts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mcts")
+            .field("config", &self.config)
+            .field("prior_value", &self.prior_value.is_some())
+            .field("nodes", &self.nodes.len())
+            .finish()
+    }
+}
+
+impl Mcts {
+    pub fn new(config: MctsConfig) -> Self {
+        Self {
+            config,
+            prior_value: None,
+            rng: StdRng::seed_from_u64(config.seed),
+            nodes: Vec::new(),
+            root_state: None,
+        }
+    }
+
+    /// The search whose leaves are evaluated by `PriorValue` instead of the rollout, like AlphaZero
+    pub fn with_prior_value<P: PriorValue + Send + 'static>(
+        config: MctsConfig,
+        prior_value: P,
+    ) -> Self {
+        Self {
+            prior_value: Some(Box::new(prior_value)),
+            ..Self::new(config)
+        }
+    }
+
+    /// Drop the tree, so the next search starts from scratch
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.root_state = None;
+    }
+
+    /// Search the best action of the agent to move, or `None` if the game is over
+    pub fn search(&mut self, state: &State) -> Option<MctsResult> {
+        if state.is_win().is_over() {
+            return None;
+        }
+
+        match self.find(state) {
+            Some(index) => self.reroot(index),
+            None => self.nodes = vec![Node::new(None, 1 - state.to_move(), 1.0)],
+        }
+        self.root_state = Some(state.clone());
+
+        let started = Instant::now();
+        for simulation in 0..self.config.simulations.max(1) {
+            if simulation > 0 && matches!(self.config.time, Some(time) if started.elapsed() >= time)
+            {
+                break;
+            }
+
+            self.simulate(state.clone());
+        }
+
+        let root = &self.nodes[0];
+        let visits = root
+            .children
+            .iter()
+            .map(|&child| {
+                let child = &self.nodes[child];
+                (child.action.clone().unwrap(), child.visits)
+            })
+            .collect::<Vec<_>>();
+
+        // the most visited one, then the best mean value, then the first one
+        let mut children = root.children.iter().map(|&child| &self.nodes[child]);
+        let mut best = children.next()?;
+        for child in children {
+            if child.visits > best.visits
+                || (child.visits == best.visits && child.mean() > best.mean())
+            {
+                best = child;
+            }
+        }
+
+        Some(MctsResult {
+            best_action: best.action.clone().unwrap(),
+            value: if root.agent_id == state.to_move() {
+                root.mean()
+            } else {
+                -root.mean()
+            },
+            visits,
+            simulations: root.visits,
+        })
+    }
+
+    // the node of the state within 2 plies from the root
+    fn find(&self, state: &State) -> Option<usize> {
+        let root_state = self.root_state.as_ref()?;
+        let mut nodes = vec![0];
+
+        for depth in 0..=2 {
+            if root_state.ply() + depth == state.ply() {
+                return nodes
+                    .into_iter()
+                    .find(|&node| self.nodes[node].zobrist == state.zobrist());
+            }
+
+            nodes = nodes
+                .iter()
+                .flat_map(|&node| self.nodes[node].children.iter().copied())
+                .filter(|&node| self.nodes[node].is_expanded)
+                .collect();
+        }
+
+        None
+    }
+
+    // make the node as the root, by copying its subtree
+    fn reroot(&mut self, root: usize) {
+        let mut nodes = vec![self.nodes[root].clone()];
+        nodes[0].action = None;
+
+        let mut index = 0;
+        while index < nodes.len() {
+            let children = mem::take(&mut nodes[index].children);
+
+            for child in children {
+                let new_child = nodes.len();
+                nodes[index].children.push(new_child);
+                nodes.push(self.nodes[child].clone());
+            }
+
+            index += 1;
+        }
+
+        self.nodes = nodes;
+    }
+
+    fn simulate(&mut self, mut state: State) {
+        let mut path = vec![0];
+        let mut node = 0;
+
+        while self.nodes[node].is_expanded && !self.nodes[node].children.is_empty() {
+            node = self.select(node);
+
+            let child = &self.nodes[node];
+            let _ = state
+                .apply(child.agent_id, child.action.as_ref().unwrap())
+                .unwrap();
+            path.push(node);
+        }
+
+        let agent_id = state.to_move();
+        let value = match state.is_win() {
+            GameResult::Win(winner) if winner == agent_id => 1.0,
+            GameResult::Win(_) => -1.0,
+            GameResult::Draw => 0.0,
+            GameResult::Ongoing => self.expand(node, &state),
+        };
+
+        // the value is for the agent to move on the leaf
+        for &node in &path {
+            let node = &mut self.nodes[node];
+
+            node.visits += 1;
+            node.value += if node.agent_id == agent_id {
+                value
+            } else {
+                -value
+            };
+        }
+    }
+
+    fn select(&self, node: usize) -> usize {
+        let parent = &self.nodes[node];
+        let total = parent.visits as f32;
+
+        let score = |child: &Node| match self.config.selection {
+            Selection::Uct { exploration } => {
+                if child.visits == 0 {
+                    f32::INFINITY
+                } else {
+                    child.mean() + exploration * (total.ln() / child.visits as f32).sqrt()
+                }
+            }
+            Selection::Puct { exploration } => {
+                child.mean() + exploration * child.prior * total.sqrt() / (1 + child.visits) as f32
+            }
+        };
+
+        // the first one of the best scores
+        let mut best = parent.children[0];
+        let mut best_score = score(&self.nodes[best]);
+
+        for &child in &parent.children[1..] {
+            let child_score = score(&self.nodes[child]);
+
+            if child_score > best_score {
+                best = child;
+                best_score = child_score;
+            }
+        }
+
+        best
+    }
+
+    // add the children of the leaf, and return the value of the leaf for the agent to move
+    fn expand(&mut self, node: usize, state: &State) -> f32 {
+        let agent_id = state.to_move();
+        let actions = Env::legal_actions(state, agent_id);
+
+        let (priors, value) = match &mut self.prior_value {
+            Some(prior_value) => {
+                let (priors, value) = prior_value.prior_value(state, &actions);
+                let sum = priors.iter().sum::<f32>();
+
+                if priors.len() == actions.len() && sum > 0.0 {
+                    (priors.iter().map(|prior| prior / sum).collect(), value)
+                } else {
+                    (vec![1.0 / actions.len() as f32; actions.len()], value)
+                }
+            }
+            None => (
+                vec![1.0 / actions.len() as f32; actions.len()],
+                self.rollout(state),
+            ),
+        };
+
+        for (action, prior) in actions.into_iter().zip(priors) {
+            let child = self.nodes.len();
+            self.nodes[node].children.push(child);
+            self.nodes.push(Node::new(Some(action), agent_id, prior));
+        }
+
+        let leaf = &mut self.nodes[node];
+        leaf.zobrist = state.zobrist();
+        leaf.is_expanded = true;
+
+        value
+    }
+
+    // the value of the state for the agent to move, by the rollout
+    fn rollout(&mut self, state: &State) -> f32 {
+        let agent_id = state.to_move();
+
+        let (policy, max_plies): (RolloutPolicy, usize) = match self.config.rollout {
+            Rollout::Evaluation => return value_of(state, agent_id),
+            Rollout::Random { max_plies } => (random_action, max_plies),
+            Rollout::Policy { policy, max_plies } => (policy, max_plies),
+        };
+
+        let mut state = state.clone();
+        for _ in 0..max_plies {
+            if state.is_win().is_over() {
+                break;
+            }
+
+            let action = match policy(&state, &mut self.rng) {
+                Some(action) => action,
+                // the agent to move cannot act, so it loses
+                None if state.to_move() == agent_id => return -1.0,
+                None => return 1.0,
+            };
+            if state.apply(state.to_move(), &action).is_err() {
+                break;
+            }
+        }
+
+        value_of(&state, agent_id)
+    }
+}
+
+// the value of the state for the agent, by the result or the evaluation
+fn value_of(state: &State, agent_id: usize) -> f32 {
+    match state.is_win() {
+        GameResult::Win(winner) if winner == agent_id => 1.0,
+        GameResult::Win(_) => -1.0,
+        GameResult::Draw => 0.0,
+        GameResult::Ongoing => {
+            let score = (search::evaluate(state) as f32 / 500.0).tanh();
+
+            if state.to_move() == agent_id {
+                score
+            } else {
+                -score
+            }
+        }
+    }
+}
+
+/// The uniformly random legal action, by sampling the candidates until one of them is legal, or `None` if there is no legal action
+pub fn random_action(state: &State, rng: &mut StdRng) -> Option<Action> {
+    let agent_id = state.to_move();
+    let mut scratch = state.clone();
+
+    for _ in 0..LEGAL_ACTION_CANDIDATES * 4 {
+        let action = legal_action_candidate(rng.gen_range(0..LEGAL_ACTION_CANDIDATES));
+
+        if let Ok(undo) = scratch.apply(agent_id, &action) {
+            scratch.undo(undo);
+            return Some(action);
+        }
+    }
+
+    // the legal actions are too rare
+    let actions = Env::legal_actions(state, agent_id);
+    if actions.is_empty() {
+        None
+    } else {
+        Some(actions[rng.gen_range(0..actions.len())].clone())
+    }
+}
+
+impl Agent for Mcts {
+    /// Search the best action, as if it is the turn of the agent, or `None` if the game is over
+    fn act(&mut self, state: &State, agent_id: usize) -> Option<Action> {
+        let mut state = state.clone();
+        state.set_turn(agent_id, state.ply());
+
+        self.search(&state).map(|result| result.best_action)
+    }
+}
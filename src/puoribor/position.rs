@@ -0,0 +1,218 @@
+use std::{error::Error, fmt};
+
+use super::{format_square, parse_square, Env, State};
+use crate::utils::*;
+
+/*
+ * The position string of State, which is 10 fields separated by spaces
+ * - the squares of the pawns of agent 0 and agent 1
+ * - the squares of horizontal walls, vertical walls, horizontal pins and vertical pins, same to State::board
+ * - the remaining walls of agent 0 and agent 1
+ * - the agent to move and the ply
+ *
+ * The squares in a field are joined by ',' row by row from the top-left, or '-' if there is none.
+ * So the initial state is "e1 e9 - - - - 10 10 0 0".
+ */
+const FIELDS: usize = 10;
+
+const CHANNELS: [&str; 4] = [
+    "horizontal wall",
+    "vertical wall",
+    "horizontal pin",
+    "vertical pin",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePositionError {
+    /// The number of fields is not 10
+    WrongFieldCount(usize),
+    /// The field cannot be parsed
+    InvalidField { field: &'static str, value: String },
+    /// The board cannot be made by the rules, like a pin without its walls
+    Inconsistent(String),
+}
+
+impl fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePositionError::WrongFieldCount(count) => {
+                write!(f, "there should be {} fields, not {}.", FIELDS, count)
+            }
+            ParsePositionError::InvalidField { field, value } => {
+                write!(f, "invalid {}: {:?}", field, value)
+            }
+            ParsePositionError::Inconsistent(reason) => write!(f, "inconsistent board: {}", reason),
+        }
+    }
+}
+
+impl Error for ParsePositionError {}
+
+macro_rules! inconsistent {
+    ($($arg:tt)*) => {
+        Err(ParsePositionError::Inconsistent(format!($($arg)*)))
+    };
+}
+
+impl State {
+    /// Encode the state into the single line position string
+    ///
+    /// The analysis mode is not a part of the position, so it is not encoded.
+    pub fn to_position_string(&self) -> String {
+        let mut fields = self
+            .players
+            .iter()
+            .map(|&pos| format_square(pos))
+            .collect::<Vec<_>>();
+
+        for board in &self.board {
+            let (width, height) = board.dim();
+
+            let squares = (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .filter(|&pos| board[pos] == 1)
+                .map(|(x, y)| format_square((x as u8, y as u8)))
+                .collect::<Vec<_>>();
+
+            fields.push(if squares.is_empty() {
+                "-".to_string()
+            } else {
+                squares.join(",")
+            });
+        }
+
+        fields.extend(self.remaining_walls.iter().map(|walls| walls.to_string()));
+        fields.push(self.to_move().to_string());
+        fields.push(self.ply().to_string());
+
+        fields.join(" ")
+    }
+
+    /// Decode the position string from `to_position_string`
+    ///
+    /// The board is rejected if it cannot be made by the rules.
+    pub fn from_position_string(s: &str) -> Result<State, ParsePositionError> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != FIELDS {
+            return Err(ParsePositionError::WrongFieldCount(fields.len()));
+        }
+
+        let invalid = |field, value: &str| ParsePositionError::InvalidField {
+            field,
+            value: value.to_string(),
+        };
+
+        let mut state = State::new();
+
+        for (agent_id, square) in fields[..2].iter().enumerate() {
+            state.players[agent_id] = parse_square(square)
+                .filter(|&(x, y)| x < 9 && y < 9)
+                .ok_or_else(|| invalid("pawn", square))?;
+        }
+
+        for (channel, field) in fields[2..6].iter().enumerate() {
+            if *field == "-" {
+                continue;
+            }
+
+            for square in field.split(',') {
+                let pos = parse_square(square).ok_or_else(|| invalid(CHANNELS[channel], square))?;
+
+                if !is_on_board(channel, pos) {
+                    return inconsistent!("{} on {} is out of board", CHANNELS[channel], square);
+                }
+                if state.board[channel][i(pos)] == 1 {
+                    return inconsistent!("{} on {} is duplicated", CHANNELS[channel], square);
+                }
+
+                state.board[channel][i(pos)] = 1;
+            }
+        }
+
+        for (agent_id, walls) in fields[6..8].iter().enumerate() {
+            state.remaining_walls[agent_id] = walls
+                .parse()
+                .ok()
+                .filter(|&walls| walls <= 10)
+                .ok_or_else(|| invalid("remaining walls", walls))?;
+        }
+
+        let to_move = match fields[8] {
+            "0" => 0,
+            "1" => 1,
+            value => return Err(invalid("agent to move", value)),
+        };
+        let ply = fields[9].parse().map_err(|_| invalid("ply", fields[9]))?;
+
+        state.set_turn(to_move, ply);
+        state.refresh_zobrist();
+        check_consistency(&state)?;
+
+        Ok(state)
+    }
+}
+
+// the positions where a wall or a pin of the channel can be, by placing and rotating
+pub(super) fn is_on_board(channel: usize, (x, y): Position) -> bool {
+    match channel {
+        0 => x < 9 && (1..9).contains(&y),
+        1 => (1..9).contains(&x) && y < 9,
+        _ => (1..9).contains(&x) && (1..9).contains(&y),
+    }
+}
+
+pub(super) fn check_consistency(state: &State) -> Result<(), ParsePositionError> {
+    if state.players[0] == state.players[1] {
+        return inconsistent!("both pawns are on {}", format_square(state.players[0]));
+    }
+
+    // each used wall places two segments, and rotating only moves or removes them
+    let segments = state.board[0]
+        .iter()
+        .chain(state.board[1].iter())
+        .filter(|&&wall| wall == 1)
+        .count();
+    let used_walls = 20usize.saturating_sub(
+        state
+            .remaining_walls
+            .iter()
+            .map(|&walls| walls as usize)
+            .sum(),
+    );
+    if segments > 2 * used_walls {
+        return inconsistent!(
+            "{} wall segments are more than {} used walls",
+            segments,
+            used_walls
+        );
+    }
+
+    for x in 1..9 {
+        for y in 1..9 {
+            let square = format_square((x as u8, y as u8));
+            let (horizontal_pin, vertical_pin) = (state.board[2][[x, y]], state.board[3][[x, y]]);
+
+            if horizontal_pin == 1 && vertical_pin == 1 {
+                return inconsistent!("horizontal and vertical pins are both on {}", square);
+            }
+
+            // the pin is the middle point of the wall, so both halves should be there
+            if horizontal_pin == 1
+                && (state.board[0][[x - 1, y]] == 0 || state.board[0][[x, y]] == 0)
+            {
+                return inconsistent!("horizontal pin on {} without its walls", square);
+            }
+
+            if vertical_pin == 1 && (state.board[1][[x, y - 1]] == 0 || state.board[1][[x, y]] == 0)
+            {
+                return inconsistent!("vertical pin on {} without its walls", square);
+            }
+        }
+    }
+
+    if !Env::is_pawns_can_win(state) {
+        return inconsistent!("a pawn cannot arrive at its winning area");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use std::{collections::BTreeMap, error::Error, fmt};
+
+use serde::{de, Deserialize, Deserializer, Serialize};
+
+use super::{Action, DrawRules, Env, GameResult, State, StepError};
+use crate::envs::BaseEnv;
+
+/// The whole history of a game, which can be replayed from its initial state
+///
+/// It is serialized with the fields as they are, and the actions are `[agent_id, action]` pairs. `State` and `Action` are serialized same to the original Python environment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameRecord {
+    /// The id of the environment and its rule version, like "puoribor-v2"
+    ///
+    /// The record of another environment or rule version is rejected on deserialization.
+    #[serde(deserialize_with = "deserialize_env")]
+    pub env: String,
+    pub initial_state: State,
+    /// The actions in the order of plies, with the agent who did it
+    pub actions: Vec<(usize, Action)>,
+    pub result: GameResult,
+    /// The draw rules of the game, which are applied on the initial state when replaying
+    ///
+    /// It is skipped if the rules are disabled, so the record without the draw rules is same to before.
+    #[serde(default, skip_serializing_if = "is_disabled")]
+    pub draw_rules: DrawRules,
+    /// Free-form information of the game, like the names of players or the date
+    pub metadata: BTreeMap<String, String>,
+}
+
+fn is_disabled(draw_rules: &DrawRules) -> bool {
+    *draw_rules == DrawRules::default()
+}
+
+fn deserialize_env<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let env = String::deserialize(deserializer)?;
+
+    if Env::is_compatible(&env) {
+        Ok(env)
+    } else {
+        Err(de::Error::custom(format!(
+            "the record is for {}, which is not compatible with {}.",
+            env,
+            Env::id()
+        )))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The action of the ply cannot be applied on the state before it
+    IllegalPly { ply: usize, error: StepError },
+    /// There is an action of the ply after the game is over
+    AfterGameOver { ply: usize },
+    /// The recorded result is different from the result of the final state
+    ResultMismatch {
+        recorded: GameResult,
+        replayed: GameResult,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::IllegalPly { ply, error } => write!(f, "illegal ply {}: {}", ply, error),
+            ReplayError::AfterGameOver { ply } => {
+                write!(f, "ply {} is played after the game is over.", ply)
+            }
+            ReplayError::ResultMismatch { recorded, replayed } => write!(
+                f,
+                "the recorded result {:?} is different from the replayed result {:?}.",
+                recorded, replayed
+            ),
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+impl GameRecord {
+    /// An empty record of the game starting from the state, with the draw rules of the state
+    pub fn new(initial_state: State) -> Self {
+        Self {
+            env: Env::id(),
+            draw_rules: initial_state.draw_rules(),
+            initial_state,
+            actions: Vec::new(),
+            result: GameResult::Ongoing,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Record the actions from the initial state, then fill the result by replaying them
+    pub fn from_actions(
+        initial_state: State,
+        actions: Vec<(usize, Action)>,
+    ) -> Result<Self, ReplayError> {
+        let mut record = GameRecord::new(initial_state);
+        record.actions = actions;
+        record.result = record.replay_to_end()?.is_win();
+
+        Ok(record)
+    }
+
+    /// Record the action of the ply without checking it, which can be checked by `validate`
+    pub fn push(&mut self, agent_id: usize, action: Action) {
+        self.actions.push((agent_id, action));
+    }
+
+    /// Iterate the initial state and every state after each ply
+    ///
+    /// The iteration is stopped after the first error.
+    pub fn replay(&self) -> Replay<'_> {
+        Replay {
+            record: self,
+            state: None,
+            ply: 0,
+            is_failed: false,
+        }
+    }
+
+    /// Check every ply and the result, and return the final state
+    pub fn validate(&self) -> Result<State, ReplayError> {
+        let state = self.replay_to_end()?;
+
+        let replayed = state.is_win();
+        if self.result != replayed {
+            return Err(ReplayError::ResultMismatch {
+                recorded: self.result,
+                replayed,
+            });
+        }
+
+        Ok(state)
+    }
+
+    fn replay_to_end(&self) -> Result<State, ReplayError> {
+        let mut state = self.initial_state();
+
+        for state_or_error in self.replay() {
+            state = state_or_error?;
+        }
+
+        Ok(state)
+    }
+
+    fn initial_state(&self) -> State {
+        let mut state = self.initial_state.clone();
+        state.set_draw_rules(self.draw_rules);
+
+        state
+    }
+}
+
+/// Iterator over the states of `GameRecord`, created by `GameRecord::replay`
+pub struct Replay<'a> {
+    record: &'a GameRecord,
+    state: Option<State>,
+    ply: usize,
+    is_failed: bool,
+}
+
+impl Iterator for Replay<'_> {
+    type Item = Result<State, ReplayError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_failed {
+            return None;
+        }
+
+        let state = match self.state {
+            None => {
+                self.state = Some(self.record.initial_state());
+                return self.state.clone().map(Ok);
+            }
+            Some(ref mut state) => state,
+        };
+
+        let (agent_id, action) = self.record.actions.get(self.ply)?;
+        let ply = self.ply;
+        self.ply += 1;
+
+        let result = if state.is_win().is_over() {
+            Err(ReplayError::AfterGameOver { ply })
+        } else {
+            state
+                .apply(*agent_id, action)
+                .map(|_| state.clone())
+                .map_err(|error| ReplayError::IllegalPly { ply, error })
+        };
+
+        self.is_failed = result.is_err();
+
+        Some(result)
+    }
+}
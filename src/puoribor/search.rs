@@ -0,0 +1,353 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use super::{Action, ActionType, Agent, Env, GameResult, State};
+use crate::utils::*;
+
+/// The score of winning on the state, which is reduced by the plies to win for preferring the faster win
+pub const WIN_SCORE: i32 = 100_000;
+
+// the scores over it are the wins
+const WIN_BOUND: i32 = WIN_SCORE - 1000;
+
+// the number of entries to clear the transposition table
+const TABLE_CAPACITY: usize = 1 << 20;
+
+/// The evaluation of the state for the agent to move, which should be in (-WIN_BOUND, WIN_BOUND)
+pub type Evaluation = fn(&State) -> i32;
+
+/// The default evaluation, by the difference of the paths and the remaining walls
+///
+/// A step of the path is 100 and a wall is 30, so the agent to move prefers shorter path than the other and saving its walls.
+pub fn evaluate(state: &State) -> i32 {
+    let agent_id = state.to_move();
+    let opposite_id = (agent_id + 1) % 2;
+
+    let distance = |agent_id: usize| {
+        let (x, y) = i(state.players[agent_id]);
+        Env::find_distances(state, agent_id)[x][y] as i32
+    };
+
+    (distance(opposite_id) - distance(agent_id)) * 100
+        + (state.remaining_walls[agent_id] as i32 - state.remaining_walls[opposite_id] as i32) * 30
+}
+
+/// The limits of the search, where the iterative deepening stops at the first limit
+///
+/// The first depth is always searched to the end, so there is the best action even if the other limits are too tight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: usize,
+    pub time: Option<Duration>,
+    pub nodes: Option<u64>,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            depth: 2,
+            time: None,
+            nodes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub best_action: Action,
+    /// The score for the agent to move, which is over `WIN_SCORE - 1000` if it can win
+    pub score: i32,
+    /// The principal variation, which starts from the best action
+    pub pv: Vec<Action>,
+    /// The depth of the last completed iteration
+    pub depth: usize,
+    /// The number of the searched states
+    pub nodes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    // the score is at least it, by the beta cutoff
+    Lower,
+    // the score is at most it, without improving alpha
+    Upper,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    depth: usize,
+    score: i32,
+    bound: Bound,
+    best_action: Option<Action>,
+}
+
+/// Negamax search with alpha-beta pruning, for the classical opponent
+///
+/// It deepens iteratively until the limits, ordering the actions by the transposition table keyed by the zobrist hash.
+/// The table is kept between the searches, which can be cleared by `clear`.
+#[derive(Debug, Clone)]
+pub struct AlphaBeta {
+    limits: SearchLimits,
+    evaluation: Evaluation,
+    table: HashMap<u64, Entry>,
+    // the states of the current search, when it is started and the depth of the current iteration
+    nodes: u64,
+    started: Instant,
+    iteration: usize,
+    is_aborted: bool,
+}
+
+impl AlphaBeta {
+    pub fn new(limits: SearchLimits) -> Self {
+        Self::with_evaluation(limits, evaluate)
+    }
+
+    pub fn with_evaluation(limits: SearchLimits, evaluation: Evaluation) -> Self {
+        Self {
+            limits,
+            evaluation,
+            table: HashMap::new(),
+            nodes: 0,
+            started: Instant::now(),
+            iteration: 0,
+            is_aborted: false,
+        }
+    }
+
+    /// Clear the transposition table
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    /// Search the best action of the agent to move, or `None` if the game is over or there is no legal action
+    pub fn search(&mut self, state: &State) -> Option<SearchResult> {
+        if state.is_win().is_over()
+            || Env::legal_actions_iter(state, state.to_move())
+                .next()
+                .is_none()
+        {
+            return None;
+        }
+
+        if self.table.len() > TABLE_CAPACITY {
+            self.table.clear();
+        }
+
+        self.nodes = 0;
+        self.started = Instant::now();
+        self.is_aborted = false;
+
+        let mut state = state.clone();
+        let mut result: Option<SearchResult> = None;
+
+        for depth in 1..=self.limits.depth.max(1) {
+            self.iteration = depth;
+
+            let mut pv = Vec::new();
+            let score = self.negamax(&mut state, depth, 0, -WIN_SCORE, WIN_SCORE, &mut pv);
+
+            // the incomplete iteration is discarded
+            if self.is_aborted {
+                break;
+            }
+
+            // the root has a legal action, so there is the best action
+            result = Some(SearchResult {
+                best_action: pv.first()?.clone(),
+                score,
+                pv,
+                depth,
+                nodes: self.nodes,
+            });
+
+            if score.abs() > WIN_BOUND {
+                break;
+            }
+        }
+
+        result.map(|result| SearchResult {
+            nodes: self.nodes,
+            ..result
+        })
+    }
+
+    // check the limits except the depth, which are never applied on the first iteration
+    fn is_limited(&mut self) -> bool {
+        if self.iteration == 1 {
+            return false;
+        }
+
+        // the time is checked on every 1024 states
+        if matches!(self.limits.nodes, Some(nodes) if self.nodes >= nodes)
+            || (self.nodes & 1023 == 0
+                && matches!(self.limits.time, Some(time) if self.started.elapsed() >= time))
+        {
+            self.is_aborted = true;
+        }
+
+        self.is_aborted
+    }
+
+    fn negamax(
+        &mut self,
+        state: &mut State,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Action>,
+    ) -> i32 {
+        self.nodes += 1;
+
+        let agent_id = state.to_move();
+        match state.is_win() {
+            // the last action wins, so it is not the agent to move
+            GameResult::Win(winner) if winner == agent_id => return WIN_SCORE - ply as i32,
+            GameResult::Win(_) => return -(WIN_SCORE - ply as i32),
+            GameResult::Draw => return 0,
+            GameResult::Ongoing => {}
+        }
+
+        if self.is_limited() {
+            return 0;
+        }
+
+        if depth == 0 {
+            return (self.evaluation)(state);
+        }
+
+        let entry = self.table.get(&state.zobrist()).cloned();
+        if let Some(entry) = &entry {
+            let score = from_table(entry.score, ply);
+
+            if ply > 0
+                && entry.depth >= depth
+                && (entry.bound == Bound::Exact
+                    || (entry.bound == Bound::Lower && score >= beta)
+                    || (entry.bound == Bound::Upper && score <= alpha))
+            {
+                return score;
+            }
+        }
+
+        let actions = order_actions(
+            state,
+            Env::legal_actions(state, agent_id),
+            entry.and_then(|entry| entry.best_action),
+        );
+
+        let original_alpha = alpha;
+        let mut best_score = -WIN_SCORE;
+        let mut best_action = None;
+        let mut child_pv = Vec::new();
+
+        for action in actions {
+            let undo = state.apply(agent_id, &action).unwrap();
+
+            child_pv.clear();
+            let score = -self.negamax(state, depth - 1, ply + 1, -beta, -alpha, &mut child_pv);
+            state.undo(undo);
+
+            if self.is_aborted {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_action = Some(action.clone());
+            }
+
+            if score > alpha {
+                alpha = score;
+
+                pv.clear();
+                pv.push(action);
+                pv.append(&mut child_pv);
+            }
+
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best_score <= original_alpha {
+            Bound::Upper
+        } else if best_score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+
+        // every action fails low on the root, but there should be the best action
+        if pv.is_empty() {
+            pv.extend(best_action.clone());
+        }
+
+        self.table.insert(
+            state.zobrist(),
+            Entry {
+                depth,
+                score: to_table(best_score, ply),
+                bound,
+                best_action,
+            },
+        );
+
+        best_score
+    }
+}
+
+// the win in the table is from the state, not from the root
+fn to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > WIN_BOUND => score + ply as i32,
+        score if score < -WIN_BOUND => score - ply as i32,
+        score => score,
+    }
+}
+
+fn from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > WIN_BOUND => score - ply as i32,
+        score if score < -WIN_BOUND => score + ply as i32,
+        score => score,
+    }
+}
+
+// the action of the table first, then the moves closer to win, the walls closer to the other pawn and the rotations
+fn order_actions(state: &State, mut actions: Vec<Action>, first: Option<Action>) -> Vec<Action> {
+    let agent_id = state.to_move();
+    let opposite = state.players[(agent_id + 1) % 2];
+    let distances = Env::find_distances(state, agent_id);
+
+    actions.sort_by_cached_key(|action| {
+        let (x, y) = action.position();
+        let near = diff_pos(opposite, (x.min(8), y.min(8))) as i32;
+
+        Reverse(if Some(action) == first.as_ref() {
+            i32::MAX
+        } else {
+            match action.action_type() {
+                ActionType::Move => 1000 - distances[x as usize][y as usize] as i32,
+                ActionType::PlaceWallHorizontally | ActionType::PlaceWallVertically => 500 - near,
+                ActionType::RotateSection => -near,
+            }
+        })
+    });
+
+    actions
+}
+
+impl Agent for AlphaBeta {
+    /// Search the best action, as if it is the turn of the agent, or `None` if the game is over
+    fn act(&mut self, state: &State, agent_id: usize) -> Option<Action> {
+        let mut state = state.clone();
+        state.set_turn(agent_id, state.ply());
+
+        self.search(&state).map(|result| result.best_action)
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use ndarray::Array3;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use super::{
+    Action, DrawRules, GameRecord, GameResult, Mcts, MctsConfig, MctsResult, PriorValue, State,
+};
+
+/*
+ * The shard of the self-play samples, whose numbers are in little endian
+ * - header: b"PRBS", the version in u32 and the number of the samples in u64, which is 16 bytes
+ * - samples: the samples in a row, each of them is 3901 bytes without padding
+ *   - observation: `State::to_observation(agent_id, false)` in (8, 9, 9) f32
+ *   - policy: the visits of MCTS normalized on `Action::to_index` in 324 f32
+ *   - outcome: 1 if the agent won, -1 if it lost and 0 for a draw in f32
+ *   - ply: the ply of the state in u32
+ *   - game: the index of the game in u32
+ *   - agent_id: the agent to move in u8
+ *
+ * So it can be read by numpy like below.
+ *   dtype = np.dtype([
+ *       ("observation", "<f4", (8, 9, 9)), ("policy", "<f4", (324,)), ("outcome", "<f4"),
+ *       ("ply", "<u4"), ("game", "<u4"), ("agent_id", "u1"),
+ *   ])
+ *   samples = np.fromfile(path, dtype=dtype, offset=16)
+ */
+const MAGIC: &[u8; 4] = b"PRBS";
+
+/// The version of the shard, which is written on the header
+pub const SHARD_VERSION: u32 = 1;
+
+const OBSERVATION_SIZE: usize = 8 * 9 * 9;
+
+const SAMPLE_SIZE: usize = (OBSERVATION_SIZE + Action::SPACE_SIZE + 1) * 4 + 4 + 4 + 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfPlayConfig {
+    pub games: usize,
+    /// The search for every ply, whose seed is added by the index of the game
+    pub mcts: MctsConfig,
+    /// The draw rules of every game, so the games are always ended
+    pub draw_rules: DrawRules,
+    /// The action is sampled by the visits until this ply for the variety of the games, then the best action is played
+    pub sampling_plies: usize,
+    pub seed: u64,
+}
+
+impl Default for SelfPlayConfig {
+    fn default() -> Self {
+        Self {
+            games: 100,
+            mcts: MctsConfig::default(),
+            draw_rules: DrawRules::standard(),
+            sampling_plies: 10,
+            seed: 0,
+        }
+    }
+}
+
+/// The training sample of a ply
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    /// The observation of the state from the agent to move, without the perspective
+    pub observation: Array3<f32>,
+    /// The target of the policy on the flat action space of `Action::to_index`, which sums to 1
+    pub policy: Vec<f32>,
+    /// The result of the game for the agent, which is 1 for a win, -1 for a loss and 0 for a draw
+    pub outcome: f32,
+    pub ply: usize,
+    pub game: usize,
+    pub agent_id: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelfPlayGame {
+    pub game: usize,
+    pub record: GameRecord,
+    pub samples: Vec<Sample>,
+}
+
+/// Play the games in parallel by MCTS with the rollout
+///
+/// The games are same for the same config, regardless of the number of threads.
+pub fn self_play(config: &SelfPlayConfig) -> Vec<SelfPlayGame> {
+    (0..config.games)
+        .into_par_iter()
+        .map(|game| play_game(config, game, Mcts::new(mcts_config(config, game))))
+        .collect()
+}
+
+/// Same to `self_play`, but MCTS is evaluated by `PriorValue` made for each game
+pub fn self_play_with<P, F>(config: &SelfPlayConfig, prior_value: F) -> Vec<SelfPlayGame>
+where
+    P: PriorValue + Send + 'static,
+    F: Fn(usize) -> P + Sync,
+{
+    (0..config.games)
+        .into_par_iter()
+        .map(|game| {
+            let mcts = Mcts::with_prior_value(mcts_config(config, game), prior_value(game));
+            play_game(config, game, mcts)
+        })
+        .collect()
+}
+
+fn mcts_config(config: &SelfPlayConfig, game: usize) -> MctsConfig {
+    MctsConfig {
+        seed: config.mcts.seed.wrapping_add(game as u64),
+        ..config.mcts
+    }
+}
+
+fn play_game(config: &SelfPlayConfig, game: usize, mut mcts: Mcts) -> SelfPlayGame {
+    let mut rng = StdRng::seed_from_u64(config.seed.wrapping_add(game as u64));
+
+    let mut state = State::new();
+    state.set_draw_rules(config.draw_rules);
+
+    let mut record = GameRecord::new(state.clone());
+    let mut samples = Vec::new();
+
+    while let Some(result) = mcts.search(&state) {
+        let agent_id = state.to_move();
+
+        let action = if state.ply() < config.sampling_plies {
+            sample_action(&result, &mut rng)
+        } else {
+            result.best_action.clone()
+        };
+
+        samples.push(Sample {
+            observation: state.to_observation(agent_id, false),
+            policy: policy_of(&result),
+            outcome: 0.0,
+            ply: state.ply(),
+            game,
+            agent_id,
+        });
+
+        let _ = state.apply(agent_id, &action).unwrap();
+        record.push(agent_id, action);
+    }
+
+    record.result = state.is_win();
+    for sample in &mut samples {
+        sample.outcome = match record.result {
+            GameResult::Win(winner) if winner == sample.agent_id => 1.0,
+            GameResult::Win(_) => -1.0,
+            _ => 0.0,
+        };
+    }
+
+    SelfPlayGame {
+        game,
+        record,
+        samples,
+    }
+}
+
+fn policy_of(result: &MctsResult) -> Vec<f32> {
+    let mut policy = vec![0.0; Action::SPACE_SIZE];
+    let total = result
+        .visits
+        .iter()
+        .map(|(_, visits)| visits)
+        .sum::<u32>()
+        .max(1) as f32;
+
+    for (action, visits) in &result.visits {
+        // every legal action is on the 9x9 board
+        policy[action.to_index().unwrap()] = *visits as f32 / total;
+    }
+
+    policy
+}
+
+// the action sampled by the visits
+fn sample_action(result: &MctsResult, rng: &mut StdRng) -> Action {
+    let total = result.visits.iter().map(|(_, visits)| visits).sum::<u32>();
+    if total == 0 {
+        return result.best_action.clone();
+    }
+
+    let mut target = rng.gen_range(0..total);
+    for (action, visits) in &result.visits {
+        if target < *visits {
+            return action.clone();
+        }
+        target -= visits;
+    }
+
+    unreachable!()
+}
+
+/// Write the samples of the games in a shard
+pub fn write_shard<W: Write>(writer: W, games: &[SelfPlayGame]) -> io::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    let count = games.iter().map(|game| game.samples.len()).sum::<usize>();
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&SHARD_VERSION.to_le_bytes())?;
+    writer.write_all(&(count as u64).to_le_bytes())?;
+
+    for sample in games.iter().flat_map(|game| &game.samples) {
+        for value in sample.observation.iter().chain(&sample.policy) {
+            writer.write_all(&value.to_le_bytes())?;
+        }
+
+        writer.write_all(&sample.outcome.to_le_bytes())?;
+        writer.write_all(&(sample.ply as u32).to_le_bytes())?;
+        writer.write_all(&(sample.game as u32).to_le_bytes())?;
+        writer.write_all(&[sample.agent_id as u8])?;
+    }
+
+    writer.flush()
+}
+
+/// Write the games in the shards of `games_per_shard` games, named like "shard-00000.prbs" in the directory
+///
+/// The directory is created if it does not exist, and the paths of the shards are returned.
+pub fn write_shards(
+    dir: &Path,
+    games: &[SelfPlayGame],
+    games_per_shard: usize,
+) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    games
+        .chunks(games_per_shard.max(1))
+        .enumerate()
+        .map(|(index, games)| {
+            let path = dir.join(format!("shard-{:05}.prbs", index));
+            write_shard(File::create(&path)?, games)?;
+
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Read the samples of the shard from `write_shard`
+pub fn read_shard<R: Read>(reader: R) -> io::Result<Vec<Sample>> {
+    let invalid = |reason: String| io::Error::new(io::ErrorKind::InvalidData, reason);
+    let mut reader = BufReader::new(reader);
+
+    let mut header = [0; 16];
+    reader.read_exact(&mut header)?;
+
+    if &header[..4] != MAGIC {
+        return Err(invalid("this is not a shard.".to_string()));
+    }
+
+    let version = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    if version != SHARD_VERSION {
+        return Err(invalid(format!("version {} is not supported.", version)));
+    }
+
+    let count = u64::from_le_bytes(header[8..].try_into().unwrap());
+    let mut samples = Vec::new();
+    let mut bytes = [0; SAMPLE_SIZE];
+
+    for _ in 0..count {
+        reader.read_exact(&mut bytes)?;
+
+        let floats = bytes[..SAMPLE_SIZE - 9]
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let (observation, rest) = floats.split_at(OBSERVATION_SIZE);
+        let integer = |offset: usize| {
+            u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap()) as usize
+        };
+
+        samples.push(Sample {
+            observation: Array3::from_shape_vec(State::OBSERVATION_SHAPE, observation.to_vec())
+                .unwrap(),
+            policy: rest[..Action::SPACE_SIZE].to_vec(),
+            outcome: rest[Action::SPACE_SIZE],
+            ply: integer(SAMPLE_SIZE - 9),
+            game: integer(SAMPLE_SIZE - 5),
+            agent_id: bytes[SAMPLE_SIZE - 1] as usize,
+        });
+    }
+
+    Ok(samples)
+}
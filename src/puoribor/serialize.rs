@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use super::{position, Action, ActionType, GameResult, State};
+use crate::utils::*;
+
+/*
+ * The serialized State, which is same to the state of the original Python environment
+ * - board: (C, W, H) shaped (6, 9, 9) array, whose (x, y) starts from top-left
+ *   - 0: one-hot encoded position of the pawn of agent 0
+ *   - 1: one-hot encoded position of the pawn of agent 1
+ *   - 2: horizontal walls between (x, y) and (x, y + 1)
+ *   - 3: vertical walls between (x, y) and (x + 1, y)
+ *   - 4: middle points of horizontal walls, on the corner of (x, y), (x + 1, y), (x, y + 1) and (x + 1, y + 1)
+ *   - 5: middle points of vertical walls, same to the channel 4
+ * - walls_remaining: the remaining walls of agent 0 and agent 1
+ * - done: if the game is over
+ * - to_move, ply: the turn of the state, which are not in the original and skipped if they are 0
+ *
+ * The original labels the walls by 1 or 2 for the agent who placed it, but State does not track it.
+ * So every wall is written as 1, and every non-zero label is read as a wall.
+ * So the state of the original is read as the initial turn of agent 0, and the state of the initial turn is written same to the original.
+ * The draw rules and the history of the positions are not written, though `done` is also true for a draw.
+ */
+#[derive(Serialize, Deserialize)]
+pub(super) struct StateFormat {
+    board: Vec<Vec<Vec<u8>>>,
+    walls_remaining: [u8; 2],
+    done: bool,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    to_move: usize,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    ply: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+// the position on State::board of the (x, y) on each channel of the board of StateFormat
+fn board_position(channel: usize, (x, y): PositionIdx) -> (usize, PositionIdx) {
+    match channel {
+        2 => (0, (x, y + 1)),
+        3 => (1, (x + 1, y)),
+        4 => (2, (x + 1, y + 1)),
+        _ => (3, (x + 1, y + 1)),
+    }
+}
+
+impl From<State> for StateFormat {
+    fn from(state: State) -> Self {
+        let mut board = vec![vec![vec![0; 9]; 9]; 6];
+
+        for (pawn, &pos) in board.iter_mut().zip(&state.players) {
+            let (x, y) = i(pos);
+            pawn[x][y] = 1;
+        }
+
+        for (channel, rows) in board.iter_mut().enumerate().skip(2) {
+            for (x, row) in rows.iter_mut().enumerate() {
+                for (y, value) in row.iter_mut().enumerate() {
+                    let (c, pos) = board_position(channel, (x, y));
+                    *value = state.board[c][pos];
+                }
+            }
+        }
+
+        StateFormat {
+            board,
+            walls_remaining: state.remaining_walls,
+            done: state.is_win().is_over(),
+            to_move: state.to_move(),
+            ply: state.ply(),
+        }
+    }
+}
+
+impl TryFrom<StateFormat> for State {
+    type Error = String;
+
+    fn try_from(format: StateFormat) -> Result<Self, Self::Error> {
+        let board = format.board;
+
+        if board.len() != 6
+            || board.iter().any(|rows| rows.len() != 9)
+            || board.iter().flatten().any(|row| row.len() != 9)
+        {
+            return Err("the board should be (6, 9, 9) shaped.".to_string());
+        }
+
+        let mut state = State::new();
+
+        for (agent_id, pawn) in board[..2].iter().enumerate() {
+            let mut pawns = (0..9)
+                .flat_map(|x| (0..9).map(move |y| (x, y)))
+                .filter(|&(x, y)| pawn[x][y] != 0);
+
+            match (pawns.next(), pawns.next()) {
+                (Some((x, y)), None) => state.players[agent_id] = (x as u8, y as u8),
+                _ => return Err(format!("there should be one pawn of agent {}.", agent_id)),
+            }
+        }
+
+        for (channel, rows) in board.iter().enumerate().skip(2) {
+            for (x, row) in rows.iter().enumerate() {
+                for (y, &value) in row.iter().enumerate() {
+                    if value == 0 {
+                        continue;
+                    }
+
+                    let (c, pos) = board_position(channel, (x, y));
+                    if !position::is_on_board(c, (pos.0 as u8, pos.1 as u8)) {
+                        return Err(format!(
+                            "the wall of channel {} on {:?} is out of board.",
+                            channel,
+                            (x, y)
+                        ));
+                    }
+
+                    state.board[c][pos] = 1;
+                }
+            }
+        }
+
+        if format.walls_remaining.iter().any(|&walls| walls > 10) {
+            return Err(format!(
+                "the remaining walls {:?} should be at most 10.",
+                format.walls_remaining
+            ));
+        }
+
+        if format.to_move > 1 {
+            return Err(format!("unknown agent {} to move.", format.to_move));
+        }
+
+        state.remaining_walls = format.walls_remaining;
+        state.set_turn(format.to_move, format.ply);
+        state.refresh_zobrist();
+
+        position::check_consistency(&state).map_err(|err| err.to_string())?;
+
+        Ok(state)
+    }
+}
+
+/*
+ * The serialized Action, which is [action_type, x, y] same to the action of the original Python environment
+ * - Move: the position to move to
+ * - PlaceWallHorizontally: the left position of the wall between y and y + 1, so y is 1 less than Action
+ * - PlaceWallVertically: the top position of the wall between x and x + 1, so x is 1 less than Action
+ * - RotateSection: the top-left position of the section
+ */
+#[derive(Serialize, Deserialize)]
+pub(super) struct ActionFormat(pub(super) u8, pub(super) i8, pub(super) i8);
+
+impl From<Action> for ActionFormat {
+    fn from(action: Action) -> Self {
+        let (x, y) = action.position();
+        let (x, y) = (x as i8, y as i8);
+
+        match action.action_type() {
+            ActionType::Move | ActionType::RotateSection => ActionFormat(action.action_type, x, y),
+            ActionType::PlaceWallHorizontally => ActionFormat(action.action_type, x, y - 1),
+            ActionType::PlaceWallVertically => ActionFormat(action.action_type, x - 1, y),
+        }
+    }
+}
+
+impl TryFrom<ActionFormat> for Action {
+    type Error = String;
+
+    fn try_from(ActionFormat(action_type, x, y): ActionFormat) -> Result<Self, Self::Error> {
+        let (x, y) = (x as i16, y as i16);
+        let (x, y) = match action_type {
+            0 | 3 => (x, y),
+            1 => (x, y + 1),
+            2 => (x + 1, y),
+            _ => return Err(format!("unknown action type {}.", action_type)),
+        };
+
+        // the position is packed in 4 bits for each axis
+        if !(0..16).contains(&x) || !(0..16).contains(&y) {
+            return Err(format!("the position {:?} is out of range.", (x, y)));
+        }
+
+        Ok(Action::new(action_type, (x as u8, y as u8)))
+    }
+}
+
+/*
+ * The serialized GameResult, which is the winner same to the original Python environment
+ * - Ongoing: null
+ * - Win: the id of the winner
+ * - Draw: "draw", which is not in the original since it does not have the draw rules
+ */
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub(super) enum ResultFormat {
+    Winner(Option<usize>),
+    Draw(String),
+}
+
+const DRAW: &str = "draw";
+
+impl From<GameResult> for ResultFormat {
+    fn from(result: GameResult) -> Self {
+        match result {
+            GameResult::Win(winner) => ResultFormat::Winner(Some(winner)),
+            GameResult::Draw => ResultFormat::Draw(DRAW.to_string()),
+            GameResult::Ongoing => ResultFormat::Winner(None),
+        }
+    }
+}
+
+impl TryFrom<ResultFormat> for GameResult {
+    type Error = String;
+
+    fn try_from(format: ResultFormat) -> Result<Self, Self::Error> {
+        match format {
+            ResultFormat::Winner(Some(winner)) if winner < 2 => Ok(GameResult::Win(winner)),
+            ResultFormat::Winner(Some(winner)) => Err(format!("unknown winner {}.", winner)),
+            ResultFormat::Winner(None) => Ok(GameResult::Ongoing),
+            ResultFormat::Draw(draw) if draw == DRAW => Ok(GameResult::Draw),
+            ResultFormat::Draw(result) => Err(format!("unknown result {:?}.", result)),
+        }
+    }
+}
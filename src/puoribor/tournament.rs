@@ -0,0 +1,415 @@
+use std::{
+    error::Error,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use super::{Agent, DrawRules, GameResult, State};
+
+// the Elo of a natural unit of the logistic ratings
+const ELO_SCALE: f64 = 400.0 / std::f64::consts::LN_10;
+
+// the z-score of the 95% confidence interval
+const Z_95: f64 = 1.96;
+
+/// The pairs of the participants to play
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Every pair of the participants
+    RoundRobin,
+    /// The challenger against every other participant, without the games between the others
+    Gauntlet { challenger: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TournamentConfig {
+    pub format: Format,
+    /// The games of each pair, where the participants alternate the agent 0 starting the game
+    pub games_per_pair: usize,
+    /// The draw rules of every game, so the games are always ended
+    pub draw_rules: DrawRules,
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            format: Format::RoundRobin,
+            games_per_pair: 2,
+            draw_rules: DrawRules::standard(),
+        }
+    }
+}
+
+/// The result of a game between the participants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameOutcome {
+    /// The participants playing the agent 0 and the agent 1
+    pub players: [usize; 2],
+    pub result: GameResult,
+    /// If the loser did an illegal action, no action or panicked, so it lost by forfeit
+    pub is_forfeit: bool,
+    pub plies: usize,
+}
+
+impl GameOutcome {
+    // the points of the participant, which is 1 for a win and 0.5 for a draw
+    fn points(&self, participant: usize) -> f64 {
+        match self.result {
+            GameResult::Win(winner) if self.players[winner] == participant => 1.0,
+            GameResult::Win(_) => 0.0,
+            _ => 0.5,
+        }
+    }
+}
+
+/// The record and the rating of a participant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// The losses by the illegal actions, which are also counted in `losses`
+    pub forfeits: usize,
+    /// The Elo rating, where the mean of the participants is 0
+    pub elo: f64,
+    /// The half width of the 95% confidence interval of `elo`
+    pub elo_interval: f64,
+}
+
+impl Standing {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    pub fn points(&self) -> f64 {
+        self.wins as f64 + self.draws as f64 * 0.5
+    }
+}
+
+/// The games and the standings of a tournament, which is displayed as the crosstable
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentResult {
+    pub games: Vec<GameOutcome>,
+    /// The standings in the order of the participants
+    pub standings: Vec<Standing>,
+}
+
+impl TournamentResult {
+    /// Compute the standings from the games, like merging the games of several tournaments
+    pub fn from_games(names: Vec<String>, games: Vec<GameOutcome>) -> Self {
+        let ratings = elo_ratings(names.len(), &games);
+
+        let standings = names
+            .into_iter()
+            .zip(ratings)
+            .enumerate()
+            .map(|(participant, (name, (elo, elo_interval)))| {
+                let mut standing = Standing {
+                    name,
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                    forfeits: 0,
+                    elo,
+                    elo_interval,
+                };
+
+                for game in games
+                    .iter()
+                    .filter(|game| game.players.contains(&participant))
+                {
+                    match game.result {
+                        GameResult::Win(winner) if game.players[winner] == participant => {
+                            standing.wins += 1
+                        }
+                        GameResult::Win(_) => {
+                            standing.losses += 1;
+                            standing.forfeits += game.is_forfeit as usize;
+                        }
+                        _ => standing.draws += 1,
+                    }
+                }
+
+                standing
+            })
+            .collect();
+
+        Self { games, standings }
+    }
+
+    /// The points of the participant against the opponent, and the number of their games
+    pub fn score_between(&self, participant: usize, opponent: usize) -> (f64, usize) {
+        self.games
+            .iter()
+            .filter(|game| {
+                game.players == [participant, opponent] || game.players == [opponent, participant]
+            })
+            .fold((0.0, 0), |(points, games), game| {
+                (points + game.points(participant), games + 1)
+            })
+    }
+}
+
+/*
+ * The crosstable sorted by Elo, like below
+ *     #  name    elo          games  w-d-l    forfeits  1        2
+ *     1  greedy   +120 ±  95  8      6-1-1    0         .        6.5/8
+ *     2  random   -120 ±  95  8      1-1-6    1         1.5/8    .
+ */
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut order = (0..self.standings.len()).collect::<Vec<_>>();
+        order.sort_by(|&a, &b| {
+            self.standings[b]
+                .elo
+                .partial_cmp(&self.standings[a].elo)
+                .unwrap()
+        });
+
+        let width = self
+            .standings
+            .iter()
+            .map(|standing| standing.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        // the columns are padded, but the lines are trimmed
+        let mut header = format!(
+            "{:>3}  {:<width$}  {:<12} {:<6} {:<8} {:<9}",
+            "#",
+            "name",
+            "elo",
+            "games",
+            "w-d-l",
+            "forfeits",
+            width = width
+        );
+        for rank in 1..=order.len() {
+            header += &format!(" {:<8}", rank);
+        }
+        writeln!(f, "{}", header.trim_end())?;
+
+        for (rank, &participant) in order.iter().enumerate() {
+            let standing = &self.standings[participant];
+
+            let mut line = format!(
+                "{:>3}  {:<width$}  {:<12} {:<6} {:<8} {:<9}",
+                rank + 1,
+                standing.name,
+                // the rounded zero is not signed by the minus
+                format!(
+                    "{:>+5.0} ±{:>4.0}",
+                    standing.elo.round() + 0.0,
+                    standing.elo_interval
+                ),
+                standing.games(),
+                format!("{}-{}-{}", standing.wins, standing.draws, standing.losses),
+                standing.forfeits,
+                width = width
+            );
+
+            for &opponent in &order {
+                let cell = match self.score_between(participant, opponent) {
+                    _ if opponent == participant => ".".to_string(),
+                    (_, 0) => "-".to_string(),
+                    (points, games) => format!("{}/{}", points, games),
+                };
+                line += &format!(" {:<8}", cell);
+            }
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TournamentError {
+    /// The challenger of the gauntlet is not one of the participants
+    UnknownChallenger {
+        challenger: usize,
+        participants: usize,
+    },
+}
+
+impl fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TournamentError::UnknownChallenger {
+                challenger,
+                participants,
+            } => write!(
+                f,
+                "the challenger {} is not one of the {} participants.",
+                challenger, participants
+            ),
+        }
+    }
+}
+
+impl Error for TournamentError {}
+
+// the constructor of the agent for every game, so the agents do not keep anything between the games
+type MakeAgent = Box<dyn FnMut() -> Box<dyn Agent>>;
+
+/// The games between the agents, where the agents are made for every game
+pub struct Tournament {
+    config: TournamentConfig,
+    participants: Vec<(String, MakeAgent)>,
+}
+
+impl fmt::Debug for Tournament {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tournament")
+            .field("config", &self.config)
+            .field(
+                "participants",
+                &self
+                    .participants
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig) -> Self {
+        Self {
+            config,
+            participants: Vec::new(),
+        }
+    }
+
+    /// Add the participant with the constructor of its agent, and return the index of it
+    pub fn add<F: FnMut() -> Box<dyn Agent> + 'static>(&mut self, name: &str, make: F) -> usize {
+        self.participants.push((name.to_string(), Box::new(make)));
+        self.participants.len() - 1
+    }
+
+    /// The pairs of the participants to play by the format
+    pub fn pairs(&self) -> Result<Vec<(usize, usize)>, TournamentError> {
+        let count = self.participants.len();
+
+        match self.config.format {
+            Format::RoundRobin => Ok((0..count)
+                .flat_map(|a| (a + 1..count).map(move |b| (a, b)))
+                .collect()),
+            Format::Gauntlet { challenger } if challenger >= count => {
+                Err(TournamentError::UnknownChallenger {
+                    challenger,
+                    participants: count,
+                })
+            }
+            Format::Gauntlet { challenger } => Ok((0..count)
+                .filter(|&opponent| opponent != challenger)
+                .map(|opponent| (challenger, opponent))
+                .collect()),
+        }
+    }
+
+    /// Play every game of the pairs
+    ///
+    /// The agent panicking on `Agent::act` forfeits the game, and the tournament goes on.
+    pub fn run(&mut self) -> Result<TournamentResult, TournamentError> {
+        let mut games = Vec::new();
+
+        for (a, b) in self.pairs()? {
+            for game in 0..self.config.games_per_pair {
+                let players = if game % 2 == 0 { [a, b] } else { [b, a] };
+                games.push(self.play(players));
+            }
+        }
+
+        let names = self
+            .participants
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        Ok(TournamentResult::from_games(names, games))
+    }
+
+    fn play(&mut self, players: [usize; 2]) -> GameOutcome {
+        let mut agents = players.map(|participant| (self.participants[participant].1)());
+
+        let mut state = State::new();
+        state.set_draw_rules(self.config.draw_rules);
+
+        while !state.is_win().is_over() {
+            let agent_id = state.to_move();
+            let agent = &mut agents[agent_id];
+            let action = panic::catch_unwind(AssertUnwindSafe(|| agent.act(&state, agent_id)));
+
+            // the agent without any action or panicked also forfeits
+            if !matches!(action, Ok(Some(action)) if state.apply(agent_id, &action).is_ok()) {
+                return GameOutcome {
+                    players,
+                    result: GameResult::Win(1 - agent_id),
+                    is_forfeit: true,
+                    plies: state.ply(),
+                };
+            }
+        }
+
+        GameOutcome {
+            players,
+            result: state.is_win(),
+            is_forfeit: false,
+            plies: state.ply(),
+        }
+    }
+}
+
+/*
+ * The maximum likelihood of the Bradley-Terry model, where a draw is a half win
+ * - every participant has a virtual draw against the rating 0, so a perfect score has a finite rating
+ * - the ratings are found by Newton's method on each participant, then the mean is moved to 0
+ * - the interval is from the Fisher information of each participant, ignoring the others' uncertainty
+ */
+fn elo_ratings(count: usize, games: &[GameOutcome]) -> Vec<(f64, f64)> {
+    let expected = |rating: f64, opponent: f64| 1.0 / (1.0 + (opponent - rating).exp());
+    let mut ratings = vec![0.0; count];
+    let mut informations = vec![0.0; count];
+
+    for _ in 0..100 {
+        for participant in 0..count {
+            let rating = ratings[participant];
+
+            // the virtual draw
+            let p = expected(rating, 0.0);
+            let mut gradient = 0.5 - p;
+            let mut information = p * (1.0 - p);
+
+            for game in games
+                .iter()
+                .filter(|game| game.players.contains(&participant))
+            {
+                let opponent = game.players[(game.players[0] == participant) as usize];
+                let p = expected(rating, ratings[opponent]);
+
+                gradient += game.points(participant) - p;
+                information += p * (1.0 - p);
+            }
+
+            ratings[participant] += gradient / information;
+            informations[participant] = information;
+        }
+    }
+
+    let mean = ratings.iter().sum::<f64>() / count.max(1) as f64;
+
+    ratings
+        .into_iter()
+        .zip(informations)
+        .map(|(rating, information)| {
+            (
+                (rating - mean) * ELO_SCALE,
+                Z_95 / information.sqrt() * ELO_SCALE,
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,100 @@
+use crate::utils::*;
+
+// the random keys of every feature of State, which are XORed into the zobrist hash
+struct Keys {
+    players: [[[u64; 9]; 9]; 2],
+    walls: [[[u64; 10]; 10]; 4],
+    remaining_walls: [[u64; 16]; 2],
+    to_move: u64,
+}
+
+// the n-th output of splitmix64, which is enough for generating the keys on compile time
+const fn key(n: u64) -> u64 {
+    let mut z = 0x5055_4f52_4942_4f52u64 // "PUORIBOR"
+        .wrapping_add((n + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+const fn generate() -> Keys {
+    let mut n = 0;
+    let mut keys = Keys {
+        players: [[[0; 9]; 9]; 2],
+        walls: [[[0; 10]; 10]; 4],
+        remaining_walls: [[0; 16]; 2],
+        to_move: 0,
+    };
+
+    let mut a = 0;
+    while a < 2 {
+        let mut x = 0;
+        while x < 9 {
+            let mut y = 0;
+            while y < 9 {
+                keys.players[a][x][y] = key(n);
+                n += 1;
+                y += 1;
+            }
+            x += 1;
+        }
+
+        let mut walls = 0;
+        while walls < 16 {
+            keys.remaining_walls[a][walls] = key(n);
+            n += 1;
+            walls += 1;
+        }
+
+        a += 1;
+    }
+
+    let mut c = 0;
+    while c < 4 {
+        let mut x = 0;
+        while x < 10 {
+            let mut y = 0;
+            while y < 10 {
+                keys.walls[c][x][y] = key(n);
+                n += 1;
+                y += 1;
+            }
+            x += 1;
+        }
+        c += 1;
+    }
+
+    keys.to_move = key(n);
+
+    keys
+}
+
+static KEYS: Keys = generate();
+
+#[inline]
+pub(super) fn player(agent_id: usize, pos: Position) -> u64 {
+    let (x, y) = i(pos);
+    debug_assert!(x < 9 && y < 9, "the pawn {:?} is out of board.", pos);
+    KEYS.players[agent_id][x][y]
+}
+
+#[inline]
+pub(super) fn wall(channel: usize, pos: PositionIdx) -> u64 {
+    KEYS.walls[channel][pos.0][pos.1]
+}
+
+#[inline]
+pub(super) fn remaining_walls(agent_id: usize, walls: u8) -> u64 {
+    debug_assert!(walls < 16, "{} remaining walls are too many.", walls);
+    KEYS.remaining_walls[agent_id][walls as usize]
+}
+
+// the key is only XORed if the agent 1 is to move
+#[inline]
+pub(super) fn to_move(agent_id: usize) -> u64 {
+    if agent_id == 1 {
+        KEYS.to_move
+    } else {
+        0
+    }
+}
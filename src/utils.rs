@@ -1,10 +1,3 @@
-#[macro_export]
-macro_rules! Err {
-    ($err_str:literal) => {
-        Err($err_str.to_string())
-    };
-}
-
 pub type Position = (u8, u8);
 pub type PositionIdx = (usize, usize);
 
@@ -11,15 +11,9 @@ pub fn i(x: (u8, u8)) -> (usize, usize) {
 }
 
 fn get_all_available_move(state: &puoribor::State, agent_id: usize) -> Vec<(u8, u8)> {
-    (0..9)
-        .flat_map(|y| (0..9).map(move |x| (x, y)))
-        .filter_map(|pos| {
-            if puoribor::Env::step(state.clone(), agent_id, puoribor::Action::new(0, pos)).is_ok() {
-                Some(pos)
-            } else {
-                None
-            }
-        })
+    puoribor::Env::legal_actions_iter(state, agent_id)
+        .filter(|action| action.action_type() == puoribor::ActionType::Move)
+        .map(|action| action.position())
         .collect::<Vec<(u8, u8)>>()
 }
 
@@ -27,9 +21,8 @@ fn get_all_available_move(state: &puoribor::State, agent_id: usize) -> Vec<(u8,
 fn run_121_play() {
     let mut state = puoribor::Env::initialize_state();
 
-    let mut iter = 0;
-    while state.is_win() == -1 {
-        let agent_id = iter % 2;
+    while !state.is_win().is_over() {
+        let agent_id = state.to_move();
 
         // print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
         println!("{}", state);
@@ -83,16 +76,10 @@ fn run_121_play() {
                 }
             }
         }
-
-        iter += 1;
     }
 
     print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
     println!("{}", state);
-    println!("iters: {}", iter);
-    if state.is_win() == 0 {
-        println!("The player 0 is won!");
-    } else {
-        println!("The player 1 is won!");
-    }
+    println!("plies: {}", state.ply());
+    println!("The result is {:?}.", state.is_win());
 }
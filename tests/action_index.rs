@@ -0,0 +1,76 @@
+use fights::puoribor::{self, Action};
+
+mod common;
+
+use common::{play, random_game};
+
+#[test]
+fn index_round_trip() {
+    for index in 0..Action::SPACE_SIZE {
+        let action = Action::from_index(index).unwrap();
+        assert_eq!(action.to_index(), Some(index));
+    }
+
+    assert_eq!(Action::from_index(Action::SPACE_SIZE), None);
+    assert_eq!(Action::new(1, (9, 3)).to_index(), None);
+}
+
+#[test]
+fn index_layout() {
+    assert_eq!(Action::new(0, (0, 0)).to_index(), Some(0));
+    assert_eq!(Action::new(0, (0, 1)).to_index(), Some(1));
+    assert_eq!(Action::new(0, (1, 0)).to_index(), Some(9));
+    assert_eq!(Action::new(3, (4, 7)).to_index(), Some(3 * 81 + 4 * 9 + 7));
+    assert_eq!(
+        Action::new(3, (8, 8)).to_index(),
+        Some(Action::SPACE_SIZE - 1)
+    );
+}
+
+#[test]
+fn walls_are_shifted_same_to_serialization() {
+    // the horizontal wall of y is between y - 1 and y, and the vertical wall of x is between x - 1 and x
+    assert_eq!(Action::new(1, (3, 1)).to_index(), Some(81 + 3 * 9));
+    assert_eq!(Action::new(2, (4, 7)).to_index(), Some(2 * 81 + 3 * 9 + 7));
+    assert_eq!(Action::new(1, (3, 0)).to_index(), None);
+    assert_eq!(Action::new(2, (0, 3)).to_index(), None);
+
+    assert_eq!(Action::from_index(81 + 3 * 9), Some(Action::new(1, (3, 1))));
+    assert_eq!(
+        Action::from_index(2 * 81 + 3 * 9 + 7),
+        Some(Action::new(2, (4, 7)))
+    );
+}
+
+#[test]
+fn index_matches_serialization() {
+    for (state, agent_id, _) in random_game(0).0 {
+        for action in puoribor::Env::legal_actions(&state, agent_id) {
+            let (action_type, x, y) = serde_json::from_value::<(usize, usize, usize)>(
+                serde_json::to_value(&action).unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(action.to_index(), Some(action_type * 81 + x * 9 + y));
+        }
+    }
+}
+
+#[test]
+fn legal_action_mask() {
+    let state = play(&[(0, (1, 3, 1)), (1, (3, 1, 0)), (0, (0, 4, 1))]);
+
+    let mask = puoribor::Env::legal_action_mask(&state, 1);
+    assert_eq!(mask.shape(), &[4, 9, 9]);
+
+    let flat = mask.as_slice().unwrap();
+    let legal_actions = puoribor::Env::legal_actions(&state, 1);
+
+    assert_eq!(
+        flat.iter().filter(|&&legal| legal).count(),
+        legal_actions.len()
+    );
+    for action in legal_actions {
+        assert!(flat[action.to_index().unwrap()]);
+    }
+}
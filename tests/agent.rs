@@ -0,0 +1,109 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, ActionType, Agent, DrawRules, GameResult, GreedyAgent, RandomAgent},
+};
+
+// play the game by the agents until it is over, and return the actions with the result
+fn play(agents: &mut [&mut dyn Agent; 2]) -> (Vec<Action>, GameResult) {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_draw_rules(DrawRules::standard());
+    let mut actions = Vec::new();
+
+    while !state.is_win().is_over() {
+        let agent_id = state.to_move();
+        let action = agents[agent_id].act(&state, agent_id).unwrap();
+
+        state = puoribor::Env::step(state, agent_id, action.clone()).unwrap();
+        actions.push(action);
+    }
+
+    (actions, state.is_win())
+}
+
+#[test]
+fn seeded_random_agents_are_deterministic() {
+    for seed in 0..5 {
+        let games = (0..2)
+            .map(|_| {
+                play(&mut [
+                    &mut RandomAgent::seeded(seed),
+                    &mut RandomAgent::seeded(seed + 100),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(games[0], games[1]);
+    }
+
+    let (actions, _) = play(&mut [&mut RandomAgent::seeded(0), &mut RandomAgent::seeded(1)]);
+    let (other, _) = play(&mut [&mut RandomAgent::seeded(2), &mut RandomAgent::seeded(1)]);
+    assert_ne!(actions, other);
+}
+
+#[test]
+fn random_agents_play_legal_actions() {
+    for _ in 0..3 {
+        play(&mut [&mut RandomAgent::new(), &mut RandomAgent::new()]);
+    }
+}
+
+#[test]
+fn random_agent_without_legal_action() {
+    // it is the turn of agent 0, so agent 1 has no legal action
+    let state = puoribor::Env::initialize_state();
+
+    assert_eq!(RandomAgent::seeded(0).act(&state, 1), None);
+    assert!(RandomAgent::seeded(0).act(&state, 0).is_some());
+}
+
+#[test]
+fn greedy_agent_moves_forward() {
+    let state = puoribor::Env::initialize_state();
+
+    // the agent to move is ahead on the same distance
+    assert_eq!(
+        GreedyAgent::new().act(&state, 0),
+        Some(Action::new(0, (4, 1)))
+    );
+}
+
+#[test]
+fn greedy_agent_blocks_when_behind() {
+    let mut state = puoribor::Env::initialize_state();
+    state.players = [(0, 0), (8, 2)];
+    state.refresh_zobrist();
+
+    let action = GreedyAgent::new().act(&state, 0).unwrap();
+    assert!(matches!(
+        action.action_type(),
+        ActionType::PlaceWallHorizontally | ActionType::PlaceWallVertically
+    ));
+
+    let walled = puoribor::Env::step(state.clone(), 0, action).unwrap();
+    assert!(
+        puoribor::Env::shortest_path_len(&walled, 1) > puoribor::Env::shortest_path_len(&state, 1)
+    );
+
+    // it moves anyway without walls
+    state.remaining_walls = [0, 10];
+    state.refresh_zobrist();
+    assert_eq!(
+        GreedyAgent::new().act(&state, 0).unwrap().action_type(),
+        ActionType::Move
+    );
+}
+
+#[test]
+fn greedy_agent_beats_random_agent() {
+    let mut wins = 0;
+
+    for seed in 0..10 {
+        let (_, result) = play(&mut [&mut GreedyAgent::new(), &mut RandomAgent::seeded(seed)]);
+        wins += (result == GameResult::Win(0)) as usize;
+
+        let (_, result) = play(&mut [&mut RandomAgent::seeded(seed), &mut GreedyAgent::new()]);
+        wins += (result == GameResult::Win(1)) as usize;
+    }
+
+    assert!(wins >= 18, "greedy agent won {} of 20 games", wins);
+}
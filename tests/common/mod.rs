@@ -0,0 +1,107 @@
+// the helpers shared by the tests, where each test uses only some of them
+#![allow(dead_code)]
+
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, GameRecord},
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// The actions of the agents, from the (agent_id, (action_type, x, y)) tuples
+pub fn actions(actions: &[(usize, (u8, u8, u8))]) -> Vec<(usize, Action)> {
+    actions
+        .iter()
+        .map(|(agent_id, action)| (*agent_id, Action::new(action.0, (action.1, action.2))))
+        .collect()
+}
+
+/// Step the actions from the state, which must be legal
+pub fn play_from(state: puoribor::State, actions: &[(usize, (u8, u8, u8))]) -> puoribor::State {
+    self::actions(actions)
+        .into_iter()
+        .fold(state, |state, (agent_id, action)| {
+            puoribor::Env::step(state, agent_id, action).unwrap()
+        })
+}
+
+/// Step the actions from the initial state, which must be legal
+pub fn play(actions: &[(usize, (u8, u8, u8))]) -> puoribor::State {
+    play_from(puoribor::Env::initialize_state(), actions)
+}
+
+/// The initial state where any agent can act, to check the rules regardless of the turn
+pub fn analysis_state() -> puoribor::State {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_analysis(true);
+    state
+}
+
+/// Play the seeded random game from the state until it is over
+///
+/// Each ply is the state before it, the agent to act and its random legal action. The state at the end is also returned.
+pub fn random_game_from(
+    state: puoribor::State,
+    seed: u64,
+) -> (Vec<(puoribor::State, usize, Action)>, puoribor::State) {
+    let mut state = state;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut plies = Vec::new();
+
+    while !state.is_win().is_over() {
+        let agent_id = state.to_move();
+
+        let legal_actions = puoribor::Env::legal_actions(&state, agent_id);
+        let action = legal_actions[rng.gen_range(0..legal_actions.len())].clone();
+        let next = puoribor::Env::step(state.clone(), agent_id, action.clone()).unwrap();
+        plies.push((state, agent_id, action));
+
+        state = next;
+    }
+
+    (plies, state)
+}
+
+/// Same to `random_game_from` from the initial state
+pub fn random_game(seed: u64) -> (Vec<(puoribor::State, usize, Action)>, puoribor::State) {
+    random_game_from(puoribor::Env::initialize_state(), seed)
+}
+
+/// The actions of the seeded random game
+pub fn random_actions(seed: u64) -> Vec<(usize, Action)> {
+    random_game(seed)
+        .0
+        .into_iter()
+        .map(|(_, agent_id, action)| (agent_id, action))
+        .collect()
+}
+
+/// Every `every` state of the seeded random game with the agent to act
+pub fn random_states(seed: u64, every: usize) -> Vec<(puoribor::State, usize)> {
+    random_game(seed)
+        .0
+        .into_iter()
+        .step_by(every)
+        .map(|(state, agent_id, _)| (state, agent_id))
+        .collect()
+}
+
+/// The record of the seeded random game from the state, whose result is from the played state
+pub fn random_record_from(state: puoribor::State, seed: u64) -> GameRecord {
+    let mut record = GameRecord::new(state.clone());
+    let (plies, end) = random_game_from(state, seed);
+
+    for (_, agent_id, action) in plies {
+        record.push(agent_id, action);
+    }
+    record.result = end.is_win();
+
+    record
+}
+
+/// The record of the seeded random game, with the seed in its metadata
+pub fn random_record(seed: u64) -> GameRecord {
+    let mut record = random_record_from(puoribor::Env::initialize_state(), seed);
+    record.metadata.insert("seed".to_string(), seed.to_string());
+
+    record
+}
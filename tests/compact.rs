@@ -0,0 +1,98 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, CompactEnv, CompactState, DrawRules, GameResult},
+};
+
+mod common;
+
+use common::{play, random_game};
+
+#[test]
+fn initial_state() {
+    assert_eq!(
+        CompactState::from_position(&puoribor::State::new()),
+        CompactEnv::initialize_state()
+    );
+    assert_eq!(
+        CompactState::new().to_position(),
+        puoribor::Env::initialize_state()
+    );
+}
+
+#[test]
+fn position_round_trip() {
+    let (plies, end) = random_game(0);
+
+    for state in plies.into_iter().map(|(state, _, _)| state).chain([end]) {
+        let round_trip = CompactState::from_position(&state).to_position();
+
+        assert_eq!(round_trip, state);
+        assert_eq!(round_trip.ply(), state.ply());
+        assert_eq!(round_trip.is_win(), state.is_win());
+    }
+
+    // the ply is not truncated
+    let state = puoribor::State::from_position_string("e1 e9 - - - - 10 10 0 70000").unwrap();
+    assert_eq!(
+        CompactState::from_position(&state).to_position().ply(),
+        70000
+    );
+
+    // the draw rules are not kept, like the position string
+    let mut state = play(&[(0, (0, 4, 1)), (1, (0, 4, 7))]);
+    state.set_draw_rules(DrawRules {
+        max_ply: Some(0),
+        repetition: None,
+    });
+    assert_eq!(state.is_win(), GameResult::Draw);
+
+    let mut round_trip = CompactState::from_position(&state).to_position();
+    assert_eq!(round_trip.is_win(), GameResult::Ongoing);
+
+    round_trip.set_draw_rules(state.draw_rules());
+    assert_eq!(round_trip.is_win(), GameResult::Draw);
+}
+
+#[test]
+fn same_to_state_on_random_play() {
+    for seed in 0..20 {
+        let mut compact = CompactEnv::initialize_state();
+        let (plies, end) = random_game(seed);
+
+        for (state, agent_id, action) in plies {
+            assert_eq!(CompactState::from_position(&state), compact);
+            assert_eq!(compact.to_position(), state);
+
+            let actions = puoribor::Env::legal_actions(&state, agent_id);
+            assert_eq!(compact.legal_actions(agent_id), actions);
+
+            compact = CompactEnv::step(compact, agent_id, action).unwrap();
+        }
+
+        assert_eq!(compact.is_win(), end.is_win());
+        assert_eq!(compact.to_string(), end.to_string());
+    }
+}
+
+#[test]
+fn same_errors_to_state() {
+    let state = play(&[
+        (0, (1, 3, 1)),
+        (1, (2, 3, 0)),
+        (0, (3, 2, 2)),
+        (1, (0, 4, 7)),
+    ]);
+
+    let compact = CompactState::from_position(&state);
+
+    for action in (0..4).flat_map(|action_type| {
+        (0..10).flat_map(move |y| (0..10).map(move |x| Action::new(action_type, (x, y))))
+    }) {
+        for agent_id in 0..2 {
+            assert_eq!(
+                CompactEnv::step(compact, agent_id, action.clone()).map(|s| s.to_position()),
+                puoribor::Env::step(state.clone(), agent_id, action.clone())
+            );
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use fights::{envs::BaseEnv, puoribor};
+use ndarray::Array2;
+
+mod common;
+
+use common::{play, random_game};
+
+#[test]
+fn distance_map_of_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    assert_eq!(
+        puoribor::Env::distance_map(&state, 0),
+        Array2::from_shape_fn([9, 9], |(_, y)| 8 - y as u8)
+    );
+    assert_eq!(
+        puoribor::Env::distance_map(&state, 1),
+        Array2::from_shape_fn([9, 9], |(_, y)| y as u8)
+    );
+}
+
+#[test]
+fn distance_map_around_walls() {
+    // close (0, 0) and (1, 0) on the top-left corner by the walls
+    let state = play(&[(0, (1, 0, 1)), (1, (2, 2, 0))]);
+    let distances = puoribor::Env::distance_map(&state, 0);
+
+    assert_eq!(distances[[0, 0]], u8::MAX);
+    assert_eq!(distances[[1, 0]], u8::MAX);
+    assert_eq!(distances[[2, 0]], 8);
+    assert_eq!(distances[[0, 1]], 7);
+    assert_eq!(distances.iter().filter(|&&d| d == u8::MAX).count(), 2);
+
+    // the map can be used as the markers of display_with
+    let marker_board = distances.mapv(|distance| (distance == u8::MAX) as u8);
+    assert!(state
+        .display_with(Some(("X", marker_board)))
+        .contains("│ X   X ┃"));
+}
+
+#[test]
+fn distance_map_agrees_with_shortest_path() {
+    for seed in 0..10 {
+        for (state, _, _) in random_game(seed).0 {
+            for agent_id in 0..2 {
+                let distances = puoribor::Env::distance_map(&state, agent_id);
+                let (x, y) = state.players[agent_id];
+
+                // every pawn can win, and jumping over the other pawn makes the path shorter
+                let distance = distances[[x as usize, y as usize]];
+                assert!(distance < 81);
+                if let Some(len) = puoribor::Env::shortest_path_len(&state, agent_id) {
+                    assert!(len <= distance as usize);
+                }
+            }
+        }
+    }
+}
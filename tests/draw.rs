@@ -0,0 +1,129 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, DrawRules, GameRecord, GameResult},
+};
+
+mod common;
+
+use common::{actions, play_from, random_record_from};
+
+// both pawns go forward and come back, which repeats the initial position
+const SHUFFLE: [(usize, (u8, u8, u8)); 4] = [
+    (0, (0, 4, 1)),
+    (1, (0, 4, 7)),
+    (0, (0, 4, 0)),
+    (1, (0, 4, 8)),
+];
+
+fn state_with(draw_rules: DrawRules) -> puoribor::State {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_draw_rules(draw_rules);
+    state
+}
+
+#[test]
+fn no_draw_by_default() {
+    let mut state = puoribor::Env::initialize_state();
+
+    for _ in 0..10 {
+        state = play_from(state, &SHUFFLE);
+    }
+
+    assert_eq!(state.repetitions(), 11);
+    assert_eq!(state.is_win(), GameResult::Ongoing);
+}
+
+#[test]
+fn draw_by_max_ply() {
+    let state = state_with(DrawRules {
+        max_ply: Some(5),
+        repetition: None,
+    });
+
+    let state = play_from(state, &SHUFFLE);
+    assert_eq!(state.is_win(), GameResult::Ongoing);
+
+    let state = play_from(state, &[(0, (0, 4, 1))]);
+    assert_eq!(state.is_win(), GameResult::Draw);
+
+    // the win is prior to the draw
+    let mut won = state;
+    won.players[0] = (0, 8);
+    assert_eq!(won.is_win(), GameResult::Win(0));
+}
+
+#[test]
+fn draw_by_threefold_repetition() {
+    let state = play_from(state_with(DrawRules::standard()), &SHUFFLE);
+    assert_eq!(state.repetitions(), 2);
+    assert_eq!(state.is_win(), GameResult::Ongoing);
+
+    let mut state = play_from(state, &SHUFFLE[..2]);
+    assert_eq!(state.repetitions(), 2);
+
+    let undos = actions(&SHUFFLE[2..])
+        .into_iter()
+        .map(|(agent_id, action)| state.apply(agent_id, &action).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(state.repetitions(), 3);
+    assert_eq!(state.is_win(), GameResult::Draw);
+
+    // undo forgets the repetition
+    for undo in undos.into_iter().rev() {
+        state.undo(undo);
+    }
+    assert_eq!(state.repetitions(), 2);
+    assert_eq!(state.is_win(), GameResult::Ongoing);
+}
+
+#[test]
+fn wall_action_resets_repetition() {
+    let state = play_from(state_with(DrawRules::standard()), &SHUFFLE);
+    let mut state = play_from(state, &SHUFFLE[..2]);
+    assert_eq!(state.repetitions(), 2);
+
+    let undo = state.apply(0, &Action::new(1, (0, 4))).unwrap();
+    assert_eq!(state.repetitions(), 1);
+
+    // the positions before the wall are back by undo
+    state.undo(undo);
+    assert_eq!(state.repetitions(), 2);
+}
+
+#[test]
+fn random_games_end() {
+    for seed in 0..10 {
+        let record = random_record_from(state_with(DrawRules::standard()), seed);
+
+        // the draw rules of the record are applied on replaying
+        let state = record.validate().unwrap();
+        assert!(state.ply() <= 200);
+    }
+}
+
+#[test]
+fn draw_rules_of_record_to_json() {
+    let mut record = GameRecord::new(state_with(DrawRules::standard()));
+    for (agent_id, action) in actions(&SHUFFLE).into_iter().cycle().take(8) {
+        record.push(agent_id, action);
+    }
+    record.result = GameResult::Draw;
+
+    let value = serde_json::to_value(&record).unwrap();
+    assert_eq!(value["result"], serde_json::json!("draw"));
+    assert_eq!(
+        value["draw_rules"],
+        serde_json::json!({"max_ply": 200, "repetition": 3})
+    );
+    assert_eq!(serde_json::from_value::<GameRecord>(value).unwrap(), record);
+
+    // the disabled rules are skipped, and read as disabled
+    let value = serde_json::to_value(GameRecord::new(puoribor::Env::initialize_state())).unwrap();
+    assert!(value.get("draw_rules").is_none());
+    assert_eq!(
+        serde_json::from_value::<GameRecord>(value)
+            .unwrap()
+            .draw_rules,
+        DrawRules::default()
+    );
+}
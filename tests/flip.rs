@@ -0,0 +1,84 @@
+use std::mem;
+
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action},
+};
+
+mod common;
+
+use common::random_states;
+
+#[test]
+fn flip_twice() {
+    for (state, _) in random_states(0, 1) {
+        assert_eq!(state.flipped().flipped(), state);
+    }
+
+    for index in 0..Action::SPACE_SIZE {
+        let action = Action::from_index(index).unwrap();
+
+        if let Some(flipped) = action.flipped() {
+            assert_eq!(flipped.flipped(), Some(action));
+        }
+    }
+}
+
+#[test]
+fn flip_initial_state() {
+    let mut state = puoribor::Env::initialize_state();
+
+    // the agent 1 to move becomes the agent 0
+    let flipped = state.flipped();
+    assert_eq!(flipped.to_move(), 1);
+
+    state.set_turn(1, 0);
+    assert_eq!(flipped, state);
+}
+
+#[test]
+fn step_commutes_with_flip() {
+    for seed in 0..3 {
+        for (state, agent_id) in random_states(seed, 5) {
+            let flipped = state.flipped();
+
+            for action in (0..3).flat_map(|action_type| {
+                (0..10).flat_map(move |y| (0..10).map(move |x| Action::new(action_type, (x, y))))
+            }) {
+                let flipped_action = match action.flipped() {
+                    Some(flipped_action) => flipped_action,
+                    None => continue,
+                };
+
+                let expected = puoribor::Env::step(state.clone(), agent_id, action.clone());
+                let actual = puoribor::Env::step(flipped.clone(), 1 - agent_id, flipped_action);
+
+                match (expected, actual) {
+                    (Ok(expected), Ok(actual)) => assert_eq!(actual, expected.flipped()),
+                    (Err(expected), Err(actual)) => {
+                        assert_eq!(mem::discriminant(&actual), mem::discriminant(&expected))
+                    }
+                    (expected, actual) => panic!(
+                        "{:?} on\n{}\nexpected: {:?}, actual: {:?}",
+                        action, state, expected, actual
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rotation_cannot_be_flipped() {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_analysis(true);
+    let state = puoribor::Env::step(state, 0, Action::new(1, (1, 1))).unwrap();
+
+    assert_eq!(Action::new(3, (0, 0)).flipped(), None);
+
+    // the mirror of clockwise rotation is counter-clockwise, so rotating the mirrored section is different
+    let rotated = puoribor::Env::step(state.clone(), 0, Action::new(3, (0, 0))).unwrap();
+    let mirrored = puoribor::Env::step(state.flipped(), 1, Action::new(3, (0, 5))).unwrap();
+
+    assert_ne!(mirrored, rotated.flipped());
+}
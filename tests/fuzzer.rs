@@ -2,102 +2,26 @@ use std::{panic, thread, time::Duration};
 
 use fights::{
     envs::BaseEnv,
-    puoribor::{self, Action},
+    puoribor::{self, Agent, DrawRules, RandomAgent},
 };
-use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::prelude::*;
 
-fn get_all_available_move(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
-    (0..9)
-        .flat_map(|y| (0..9).map(move |x| (x, y)))
-        .filter_map(|pos| {
-            let action = puoribor::Action::new(0, pos);
-
-            if puoribor::Env::step(state.clone(), agent_id, action.clone()).is_ok() {
-                Some(action)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Action>>()
-}
-
-fn get_all_available_place_wall(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
-    if state.remaining_walls[agent_id] == 0 {
-        return Vec::new();
-    }
-
-    // check place horizontal wall
-    let horizontals = (0..9)
-        .flat_map(|y| (0..10).map(move |x| (x, y)))
-        .filter_map(|pos| {
-            let action = puoribor::Action::new(1, pos);
-
-            if puoribor::Env::step(state.clone(), agent_id, action.clone()).is_ok() {
-                Some(action)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Action>>();
-
-    // check place horizontal wall
-    let verticals = (0..10)
-        .flat_map(|y| (0..9).map(move |x| (x, y)))
-        .filter_map(|pos| {
-            let action = puoribor::Action::new(2, pos);
-
-            if puoribor::Env::step(state.clone(), agent_id, action.clone()).is_ok() {
-                Some(action)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Action>>();
-
-    [horizontals, verticals].concat()
-}
-
-fn get_all_available_rotate_section(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
-    if state.remaining_walls[agent_id] < 2 {
-        return Vec::new();
-    }
-
-    (0..6)
-        .flat_map(|y| (0..6).map(move |x| (x, y)))
-        .filter_map(|pos| {
-            let action = puoribor::Action::new(3, pos);
-
-            if puoribor::Env::step(state.clone(), agent_id, action.clone()).is_ok() {
-                Some(action)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<Action>>()
-}
-
-fn get_all_available_action(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
-    [
-        get_all_available_move(state, agent_id),
-        get_all_available_place_wall(state, agent_id),
-        get_all_available_rotate_section(state, agent_id),
-    ]
-    .concat()
-}
-
 fn random_play_with_seed(seed: u64, delay: u64, slient: bool) {
     let mut state = puoribor::Env::initialize_state();
-    let mut rng = StdRng::seed_from_u64(seed);
+    // the pawns can shuffle forever, so the game should be ended
+    state.set_draw_rules(DrawRules {
+        max_ply: Some(1000),
+        repetition: Some(3),
+    });
+    let mut agent = RandomAgent::seeded(seed);
 
     let mut iter = 0;
-    while state.is_win() == -1 {
+    while !state.is_win().is_over() {
         thread::sleep(Duration::from_millis(delay));
 
-        let agent_id = iter % 2;
+        let agent_id = state.to_move();
 
-        let actions = get_all_available_action(&state, agent_id);
-        let action = actions[rng.gen_range(0..actions.len())].clone();
+        let action = agent.act(&state, agent_id).unwrap();
 
         if !slient {
             print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
@@ -120,11 +44,7 @@ fn random_play_with_seed(seed: u64, delay: u64, slient: bool) {
         print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
         println!("{}", state);
         println!("iters: {}", iter);
-        if state.is_win() == 0 {
-            println!("The player 0 is won!");
-        } else {
-            println!("The player 1 is won!");
-        }
+        println!("The result is {:?}.", state.is_win());
     }
 }
 
@@ -0,0 +1,139 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{
+        self, Action, DrawRules, GameLogReader, GameLogWriter, GameRecord, GameResult, LogError,
+    },
+};
+
+mod common;
+
+use common::random_record;
+
+fn write_log(records: &[GameRecord]) -> Vec<u8> {
+    let mut writer = GameLogWriter::new(Vec::new()).unwrap();
+    for record in records {
+        writer.write(record).unwrap();
+    }
+
+    writer.finish().unwrap()
+}
+
+fn read_log(log: &[u8]) -> Result<Vec<GameRecord>, LogError> {
+    GameLogReader::new(log)?.collect()
+}
+
+#[test]
+fn round_trip_of_random_games() {
+    let records = (0..10).map(random_record).collect::<Vec<_>>();
+    let log = write_log(&records);
+
+    assert_eq!(read_log(&log).unwrap(), records);
+
+    // one byte for each action after the header, and an order of magnitude smaller than JSON
+    let plies = records.iter().map(|r| r.actions.len()).sum::<usize>();
+    let json = records
+        .iter()
+        .map(|r| serde_json::to_string(r).unwrap().len())
+        .sum::<usize>();
+    assert!(log.len() < 32 + plies * 9 / 8 + records.len() * 32);
+    assert!(log.len() * 10 < json);
+}
+
+#[test]
+fn round_trip_of_custom_records() {
+    let mut record = random_record(0);
+    let state = record.replay().nth(5).unwrap().unwrap();
+
+    // the record from the middle of the game, by the same agent
+    let mut custom = GameRecord::new(state);
+    custom.push(1, Action::new(1, (0, 8)));
+    custom.push(1, Action::new(3, (5, 5)));
+    custom.push(0, Action::new(2, (8, 7)));
+
+    record
+        .metadata
+        .insert("name".to_string(), "퓨오리보르".to_string());
+
+    // the drawn record
+    let mut drawn = GameRecord::new(puoribor::Env::initialize_state());
+    drawn.draw_rules = DrawRules {
+        max_ply: Some(4),
+        repetition: None,
+    };
+    drawn.push(0, Action::new(0, (4, 1)));
+    drawn.push(1, Action::new(0, (4, 7)));
+    drawn.push(0, Action::new(0, (4, 0)));
+    drawn.push(1, Action::new(0, (4, 8)));
+    drawn.result = GameResult::Draw;
+    assert!(drawn.validate().is_ok());
+
+    let records = vec![
+        GameRecord::new(puoribor::Env::initialize_state()),
+        custom,
+        record,
+        drawn,
+    ];
+    assert_eq!(read_log(&write_log(&records)).unwrap(), records);
+}
+
+#[test]
+fn reject_broken_logs() {
+    let log = write_log(&[random_record(0), random_record(1)]);
+
+    assert!(matches!(read_log(b"JSON\x01"), Err(LogError::BadMagic)));
+    assert!(matches!(
+        read_log(b"PRBR\x02"),
+        Err(LogError::UnsupportedVersion(2))
+    ));
+    assert!(matches!(
+        read_log(b"PRBR\x00"),
+        Err(LogError::UnsupportedVersion(0))
+    ));
+
+    // the log of another environment
+    assert!(matches!(
+        read_log(b"PRBR\x01\x0b\x00quoridor-v1"),
+        Err(LogError::IncompatibleEnv(env)) if env == "quoridor-v1"
+    ));
+
+    let mut broken = log.clone();
+    broken[30] ^= 0x10;
+    assert!(matches!(
+        read_log(&broken),
+        Err(LogError::ChecksumMismatch { .. })
+    ));
+
+    // the log is cut in the middle of the record
+    assert!(matches!(
+        read_log(&log[..log.len() - 1]),
+        Err(LogError::Io(_))
+    ));
+
+    // the records before the error are read
+    let mut reader = GameLogReader::new(&broken[..]).unwrap();
+    assert!(reader.next().unwrap().is_err());
+
+    let mut reader = GameLogReader::new(&log[..log.len() - 1]).unwrap();
+    assert_eq!(reader.next().unwrap().unwrap(), random_record(0));
+    assert!(reader.next().unwrap().is_err());
+}
+
+#[test]
+fn reject_unencodable_records() {
+    let mut record = GameRecord::new(puoribor::Env::initialize_state());
+    record.push(0, Action::new(1, (8, 1)));
+
+    let mut writer = GameLogWriter::new(Vec::new()).unwrap();
+    assert!(matches!(
+        writer.write(&record),
+        Err(LogError::Unencodable(_))
+    ));
+
+    // the record of another environment
+    let mut record = GameRecord::new(puoribor::Env::initialize_state());
+    record.env = "quoridor-v1".to_string();
+    assert!(matches!(
+        writer.write(&record),
+        Err(LogError::Unencodable(_))
+    ));
+}
@@ -0,0 +1,52 @@
+"""Generate the golden states of tests/serialize.rs by the original Python environment.
+
+    pip install fights
+    python tests/golden/generate.py
+
+The actions are the sample game of tests/serialize.rs in the serialized form, [action_type, x, y].
+game.json is not generated, since the original does not have the game record.
+"""
+
+import json
+from pathlib import Path
+
+import numpy as np
+from fights.envs import puoribor
+
+GOLDEN = Path(__file__).parent
+
+SAMPLE_GAME = [
+    (0, [1, 3, 0]),
+    (1, [3, 0, 0]),
+    (0, [2, 5, 4]),
+    (1, [0, 4, 7]),
+    (0, [0, 5, 0]),
+]
+
+
+def to_json(state):
+    return {
+        "board": state.board.tolist(),
+        "walls_remaining": state.walls_remaining.tolist(),
+        "done": bool(state.done),
+    }
+
+
+def write(name, state):
+    with open(GOLDEN / name, "w") as f:
+        json.dump(to_json(state), f, separators=(",", ":"))
+        f.write("\n")
+
+
+def main():
+    env = puoribor.PuoriborEnv()
+    state = env.initialize_state()
+    write("initial_state.json", state)
+
+    for agent_id, action in SAMPLE_GAME:
+        state = env.step(state, agent_id, np.array(action))
+    write("state.json", state)
+
+
+if __name__ == "__main__":
+    main()
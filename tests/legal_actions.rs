@@ -0,0 +1,60 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action},
+};
+
+mod common;
+
+use common::random_game;
+
+// try every (action_type, x, y) by stepping on the cloned state
+fn brute_force_legal_actions(state: &puoribor::State, agent_id: usize) -> Vec<Action> {
+    (0..4)
+        .flat_map(|action_type| {
+            (0..10).flat_map(move |y| (0..10).map(move |x| Action::new(action_type, (x, y))))
+        })
+        .filter(|action| puoribor::Env::step(state.clone(), agent_id, action.clone()).is_ok())
+        .collect()
+}
+
+fn assert_same_actions(state: &puoribor::State, agent_id: usize) {
+    assert_eq!(
+        puoribor::Env::legal_actions(state, agent_id),
+        brute_force_legal_actions(state, agent_id),
+        "\n{}",
+        state
+    );
+}
+
+#[test]
+fn legal_actions_on_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    assert_same_actions(&state, 0);
+    assert_same_actions(&state, 1);
+
+    // 3 moves, 64 horizontal walls, 64 vertical walls and 36 rotations
+    assert_eq!(
+        puoribor::Env::legal_actions(&state, 0).len(),
+        3 + 64 + 64 + 36
+    );
+}
+
+#[test]
+fn legal_actions_iter_is_same_to_vec() {
+    let state = puoribor::Env::initialize_state();
+
+    assert_eq!(
+        puoribor::Env::legal_actions_iter(&state, 0).collect::<Vec<_>>(),
+        puoribor::Env::legal_actions(&state, 0)
+    );
+}
+
+#[test]
+fn legal_actions_on_random_play() {
+    for seed in 0..10 {
+        for (state, agent_id, _) in random_game(seed).0 {
+            assert_same_actions(&state, agent_id);
+        }
+    }
+}
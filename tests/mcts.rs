@@ -0,0 +1,173 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{
+        self, random_action, Action, Agent, DrawRules, GameResult, Mcts, MctsConfig, RandomAgent,
+        Rollout, Selection,
+    },
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+fn config(simulations: usize) -> MctsConfig {
+    MctsConfig {
+        simulations,
+        ..MctsConfig::default()
+    }
+}
+
+fn play(agents: &mut [&mut dyn Agent; 2]) -> GameResult {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_draw_rules(DrawRules::standard());
+
+    while !state.is_win().is_over() {
+        let agent_id = state.to_move();
+        let action = agents[agent_id].act(&state, agent_id).unwrap();
+        state = puoribor::Env::step(state, agent_id, action).unwrap();
+    }
+
+    state.is_win()
+}
+
+#[test]
+fn search_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    for selection in [
+        Selection::Uct { exploration: 1.4 },
+        Selection::Puct { exploration: 1.5 },
+    ] {
+        let result = Mcts::new(MctsConfig {
+            selection,
+            ..config(300)
+        })
+        .search(&state)
+        .unwrap();
+
+        assert_eq!(result.simulations, 300);
+        assert_eq!(
+            result.visits.iter().map(|(_, visits)| visits).sum::<u32>(),
+            299
+        );
+        assert_eq!(
+            result
+                .visits
+                .iter()
+                .map(|(action, _)| action.clone())
+                .collect::<Vec<_>>(),
+            puoribor::Env::legal_actions(&state, 0)
+        );
+        assert!((-1.0..=1.0).contains(&result.value));
+        assert!(puoribor::Env::step(state.clone(), 0, result.best_action).is_ok());
+    }
+}
+
+#[test]
+fn same_seed_same_search() {
+    let state = puoribor::Env::initialize_state();
+
+    for rollout in [Rollout::Evaluation, Rollout::Random { max_plies: 10 }] {
+        let search = |seed| {
+            Mcts::new(MctsConfig {
+                rollout,
+                seed,
+                ..config(200)
+            })
+            .search(&state)
+            .unwrap()
+        };
+
+        assert_eq!(search(0), search(0));
+    }
+}
+
+#[test]
+fn find_the_win() {
+    let mut state = puoribor::Env::initialize_state();
+    state.players = [(4, 7), (0, 4)];
+    state.refresh_zobrist();
+
+    let result = Mcts::new(config(500)).search(&state).unwrap();
+    assert_eq!(result.best_action, Action::new(0, (4, 8)));
+    assert!(result.value > 0.0);
+}
+
+#[test]
+fn rollout_without_legal_action() {
+    // the pawn of the agent 1 is boxed by the walls without any remaining wall, which cannot be made by the rules
+    let mut state = puoribor::Env::initialize_state();
+    state.board[0][[4, 8]] = 1;
+    state.board[1][[4, 8]] = 1;
+    state.board[1][[5, 8]] = 1;
+    state.remaining_walls = [10, 0];
+    state.refresh_zobrist();
+
+    let boxed = puoribor::Env::step(state.clone(), 0, Action::new(0, (4, 1))).unwrap();
+    assert!(puoribor::Env::legal_actions(&boxed, 1).is_empty());
+    assert_eq!(random_action(&boxed, &mut StdRng::seed_from_u64(0)), None);
+
+    // the rollouts from the boxed pawn are lost by the agent 1
+    let result = Mcts::new(MctsConfig {
+        rollout: Rollout::Random { max_plies: 20 },
+        ..config(200)
+    })
+    .search(&state)
+    .unwrap();
+    assert!(result.value > 0.0);
+}
+
+#[test]
+fn reuse_tree() {
+    let state = puoribor::Env::initialize_state();
+    let mut mcts = Mcts::new(config(200));
+
+    let result = mcts.search(&state).unwrap();
+    let state = puoribor::Env::step(state, 0, result.best_action).unwrap();
+
+    // the visits of the next state are kept
+    let result = mcts.search(&state).unwrap();
+    assert!(result.simulations > 200);
+
+    let state = puoribor::Env::step(state, 1, result.best_action).unwrap();
+    let result = mcts.search(&state).unwrap();
+    assert!(result.simulations > 200);
+
+    mcts.clear();
+    assert_eq!(mcts.search(&state).unwrap().simulations, 200);
+}
+
+#[test]
+fn prior_value_is_used() {
+    let state = puoribor::Env::initialize_state();
+
+    // every prior is on the first action, and the value is even until the game is over
+    let mut calls = 0;
+    let mut mcts = Mcts::with_prior_value(
+        MctsConfig {
+            selection: Selection::Puct { exploration: 1.0 },
+            ..config(50)
+        },
+        move |_: &puoribor::State, actions: &[Action]| {
+            calls += 1;
+            assert!(calls <= 50);
+
+            let mut priors = vec![0.0; actions.len()];
+            priors[0] = 1.0;
+            (priors, 0.0)
+        },
+    );
+
+    let result = mcts.search(&state).unwrap();
+    assert_eq!(
+        result.best_action,
+        puoribor::Env::legal_actions(&state, 0)[0]
+    );
+    assert_eq!(result.visits[0].1, 49);
+}
+
+#[test]
+fn mcts_beats_random_agent() {
+    let result = play(&mut [&mut Mcts::new(config(100)), &mut RandomAgent::seeded(0)]);
+    assert_eq!(result, GameResult::Win(0));
+
+    let result = play(&mut [&mut RandomAgent::seeded(1), &mut Mcts::new(config(100))]);
+    assert_eq!(result, GameResult::Win(1));
+}
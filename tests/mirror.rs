@@ -0,0 +1,84 @@
+use std::mem;
+
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, ActionType},
+};
+
+mod common;
+
+use common::random_states;
+
+#[test]
+fn mirror_twice() {
+    for (state, _) in random_states(0, 1) {
+        assert_eq!(state.mirrored().mirrored(), state);
+    }
+
+    for index in 0..Action::SPACE_SIZE {
+        let action = Action::from_index(index).unwrap();
+
+        if let Some(mirrored) = action.mirrored() {
+            assert_eq!(mirrored.mirrored(), Some(action));
+        }
+    }
+}
+
+#[test]
+fn step_commutes_with_mirror() {
+    for seed in 0..3 {
+        for (state, agent_id) in random_states(seed, 5) {
+            let mirrored = state.mirrored();
+
+            for action in (0..3).flat_map(|action_type| {
+                (0..10).flat_map(move |y| (0..10).map(move |x| Action::new(action_type, (x, y))))
+            }) {
+                let mirrored_action = match action.mirrored() {
+                    Some(mirrored_action) => mirrored_action,
+                    None => continue,
+                };
+
+                let expected = puoribor::Env::step(state.clone(), agent_id, action.clone());
+                let actual = puoribor::Env::step(mirrored.clone(), agent_id, mirrored_action);
+
+                match (expected, actual) {
+                    (Ok(expected), Ok(actual)) => assert_eq!(actual, expected.mirrored()),
+                    (Err(expected), Err(actual)) => {
+                        assert_eq!(mem::discriminant(&actual), mem::discriminant(&expected))
+                    }
+                    (expected, actual) => panic!(
+                        "{:?} on\n{}\nexpected: {:?}, actual: {:?}",
+                        action, state, expected, actual
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn mirror_report() {
+    for (state, agent_id) in random_states(1, 7) {
+        let legal_actions = puoribor::Env::legal_actions(&state, agent_id);
+        let report = puoribor::Env::mirror_report(&state, agent_id);
+
+        assert_eq!(
+            report.mirrored.len() + report.unmirrorable.len(),
+            legal_actions.len()
+        );
+
+        // only the rotations have no mirror
+        assert_eq!(
+            report.unmirrorable,
+            legal_actions
+                .into_iter()
+                .filter(|action| action.action_type() == ActionType::RotateSection)
+                .collect::<Vec<_>>()
+        );
+
+        for (action, mirrored) in report.mirrored {
+            assert!(puoribor::Env::step(state.mirrored(), agent_id, mirrored.clone()).is_ok());
+            assert_eq!(action.mirrored(), Some(mirrored));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, ParseActionError},
+};
+
+#[test]
+fn display_actions() {
+    assert_eq!(Action::new(0, (4, 1)).to_string(), "e2");
+    assert_eq!(Action::new(1, (3, 2)).to_string(), "h d3");
+    assert_eq!(Action::new(2, (3, 2)).to_string(), "v d3");
+    assert_eq!(Action::new(3, (2, 1)).to_string(), "r c2");
+    assert_eq!(Action::new(0, (8, 8)).to_string(), "i9");
+}
+
+#[test]
+fn parse_actions() {
+    assert_eq!("e2".parse(), Ok(Action::new(0, (4, 1))));
+    assert_eq!("h d3".parse(), Ok(Action::new(1, (3, 2))));
+    assert_eq!("  v   d3 ".parse(), Ok(Action::new(2, (3, 2))));
+    assert_eq!("r c2".parse(), Ok(Action::new(3, (2, 1))));
+    assert_eq!("j10".parse(), Ok(Action::new(0, (9, 9))));
+}
+
+#[test]
+fn round_trip_of_every_action() {
+    for action in (0..Action::SPACE_SIZE).filter_map(Action::from_index) {
+        assert_eq!(action.to_string().parse(), Ok(action));
+    }
+
+    // every legal action on the initial state
+    let state = puoribor::Env::initialize_state();
+    for action in puoribor::Env::legal_actions(&state, 0) {
+        assert_eq!(action.to_string().parse::<Action>().unwrap(), action);
+    }
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!("".parse::<Action>(), Err(ParseActionError::Empty));
+    assert_eq!(
+        "x d3".parse::<Action>(),
+        Err(ParseActionError::UnknownType("x".to_string()))
+    );
+    assert_eq!(
+        "h d3 e4".parse::<Action>(),
+        Err(ParseActionError::TooManyTokens("h d3 e4".to_string()))
+    );
+
+    for square in ["e", "e0", "e17", "z1", "E2", "2e", "e+2", "é2"] {
+        assert_eq!(
+            square.parse::<Action>(),
+            Err(ParseActionError::InvalidSquare(square.to_string()))
+        );
+    }
+
+    assert_eq!(
+        "r c".parse::<Action>().unwrap_err().to_string(),
+        "invalid square \"c\", which should be a file from 'a' to 'p' and a rank from 1 to 16, like \"e2\"."
+    );
+}
@@ -0,0 +1,64 @@
+use fights::{envs::BaseEnv, puoribor};
+
+mod common;
+
+use common::play;
+
+#[test]
+fn observation_of_initial_state() {
+    let state = puoribor::Env::initialize_state();
+    let observation = state.to_observation(0, false);
+
+    assert_eq!(observation.shape(), &puoribor::State::OBSERVATION_SHAPE);
+    assert_eq!(observation[[0, 4, 0]], 1.0);
+    assert_eq!(observation[[1, 4, 8]], 1.0);
+    assert_eq!(
+        observation.iter().filter(|&&v| v == 1.0).count(),
+        2 + 2 * 81
+    );
+
+    // the initial state is symmetric for both agents
+    assert_eq!(state.to_observation(1, true), observation);
+    assert_ne!(state.to_observation(1, false), observation);
+}
+
+#[test]
+fn observation_of_walls() {
+    let state = play(&[
+        (0, (1, 3, 1)),
+        (1, (2, 6, 4)),
+        (0, (0, 5, 0)),
+        (1, (1, 0, 8)),
+    ]);
+
+    let observation = state.to_observation(0, false);
+
+    assert_eq!(observation[[0, 5, 0]], 1.0);
+    assert_eq!(observation[[1, 4, 8]], 1.0);
+
+    // horizontal wall on top of (3, 1) and (4, 1), which is between y = 0 and y = 1
+    assert_eq!(observation[[2, 3, 0]], 1.0);
+    assert_eq!(observation[[2, 4, 0]], 1.0);
+    assert_eq!(observation[[4, 3, 0]], 1.0);
+
+    // vertical wall on left of (6, 4) and (6, 5), which is between x = 5 and x = 6
+    assert_eq!(observation[[3, 5, 4]], 1.0);
+    assert_eq!(observation[[3, 5, 5]], 1.0);
+    assert_eq!(observation[[5, 5, 4]], 1.0);
+
+    assert_eq!(observation[[6, 0, 0]], 0.9);
+    assert_eq!(observation[[7, 8, 8]], 0.8);
+
+    // flipped for the agent 1
+    let flipped = state.to_observation(1, true);
+
+    assert_eq!(flipped[[0, 4, 0]], 1.0);
+    assert_eq!(flipped[[1, 5, 8]], 1.0);
+    assert_eq!(flipped[[2, 3, 7]], 1.0);
+    assert_eq!(flipped[[2, 0, 0]], 1.0);
+    assert_eq!(flipped[[3, 5, 3]], 1.0);
+    assert_eq!(flipped[[3, 5, 4]], 1.0);
+    assert_eq!(flipped[[5, 5, 3]], 1.0);
+    assert_eq!(flipped[[6, 0, 0]], 0.8);
+    assert_eq!(flipped[[7, 0, 0]], 0.9);
+}
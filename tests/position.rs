@@ -0,0 +1,135 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, ParsePositionError},
+};
+
+mod common;
+
+use common::{play, random_game};
+
+fn inconsistent(s: &str) -> bool {
+    matches!(
+        puoribor::State::from_position_string(s),
+        Err(ParsePositionError::Inconsistent(_))
+    )
+}
+
+#[test]
+fn position_string_of_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    assert_eq!(state.to_position_string(), "e1 e9 - - - - 10 10 0 0");
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 10 10 0 0"),
+        Ok(state.clone())
+    );
+
+    let parsed = puoribor::State::from_position_string("e1 e9 - - - - 10 10 1 3").unwrap();
+    assert_eq!((parsed.to_move(), parsed.ply()), (1, 3));
+    assert_ne!(parsed, state);
+    assert_ne!(parsed.zobrist(), state.zobrist());
+}
+
+#[test]
+fn position_string_with_split_wall() {
+    // the rotation takes the left half of the horizontal wall, and leaves the right half on e2 without its pin
+    let state = play(&[(0, (1, 3, 1)), (1, (3, 0, 0))]);
+    let s = state.to_position_string();
+
+    assert_eq!(s, "e1 e9 e2 d4 - - 9 8 0 2");
+    assert_eq!(puoribor::State::from_position_string(&s), Ok(state));
+}
+
+#[test]
+fn position_string_of_random_games() {
+    for seed in 0..10 {
+        for (state, _, _) in random_game(seed).0 {
+            let parsed =
+                puoribor::State::from_position_string(&state.to_position_string()).unwrap();
+            assert_eq!(parsed, state);
+            assert_eq!(parsed.zobrist(), state.zobrist());
+        }
+    }
+}
+
+#[test]
+fn reject_invalid_fields() {
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 10 10"),
+        Err(ParsePositionError::WrongFieldCount(8))
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 10 10 0"),
+        Err(ParsePositionError::WrongFieldCount(9))
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 j9 - - - - 10 10 0 0"),
+        Err(ParsePositionError::InvalidField {
+            field: "pawn",
+            value: "j9".to_string()
+        })
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 d2,x - - - 10 10 0 0"),
+        Err(ParsePositionError::InvalidField {
+            field: "horizontal wall",
+            value: "x".to_string()
+        })
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 11 10 0 0"),
+        Err(ParsePositionError::InvalidField {
+            field: "remaining walls",
+            value: "11".to_string()
+        })
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 10 10 2 0"),
+        Err(ParsePositionError::InvalidField {
+            field: "agent to move",
+            value: "2".to_string()
+        })
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 - - - - 10 10 0 -1"),
+        Err(ParsePositionError::InvalidField {
+            field: "ply",
+            value: "-1".to_string()
+        })
+    );
+}
+
+#[test]
+fn reject_inconsistent_boards() {
+    assert!(!inconsistent("e1 e9 d2,e2 - e2 - 9 10 0 0"));
+
+    // pin without its walls
+    assert!(inconsistent("e1 e9 d2 - e2 - 9 10 0 0"));
+    assert!(inconsistent("e1 e9 - - - e2 9 10 0 0"));
+    // walls on the edges of the board
+    assert!(inconsistent("e1 e9 e1 - - - 10 10 0 0"));
+    assert!(inconsistent("e1 e9 - a1 - - 10 10 0 0"));
+    // both pins on the same point
+    assert!(inconsistent("e1 e9 d2,e2 e1,e2 e2 e2 8 10 0 0"));
+    assert!(inconsistent("e1 e9 d2,d2 - - - 10 10 0 0"));
+    assert!(inconsistent("e1 e1 - - - - 10 10 0 0"));
+    // the pawn on e1 is trapped
+    assert!(inconsistent("e1 e9 e2 e1,f1 - - 9 9 0 0"));
+    // more wall segments than the used walls
+    assert!(inconsistent("e1 e9 d2,e2 - - - 10 10 0 0"));
+    assert!(inconsistent("e1 e9 d2,e2,d5 - - - 9 10 0 0"));
+    assert!(!inconsistent("e1 e9 d2,e2,d5 - - - 9 9 0 0"));
+
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 d2 - e2 - 9 10 0 0")
+            .unwrap_err()
+            .to_string(),
+        "inconsistent board: horizontal pin on e2 without its walls"
+    );
+    assert_eq!(
+        puoribor::State::from_position_string("e1 e9 d2,e2,d5 - - - 9 10 0 0")
+            .unwrap_err()
+            .to_string(),
+        "inconsistent board: 3 wall segments are more than 1 used walls"
+    );
+}
@@ -0,0 +1,86 @@
+use std::collections::VecDeque;
+
+use fights::puoribor::{self, ReachabilityCache};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+mod common;
+
+use common::random_game;
+
+// the original path check replaced by the cache, which is the BFS of the pawn to its winning area
+fn is_pawn_can_win(agent_id: usize, state: &puoribor::State) -> bool {
+    let mut queue = VecDeque::new();
+
+    let win_y = if agent_id == 0 { 8 } else { 0 };
+    queue.push_back(state.players[agent_id]);
+
+    let mut visited = [[false; 9]; 9];
+
+    while let Some((x, y)) = queue.pop_front() {
+        if y == win_y {
+            return true;
+        }
+
+        let (ux, uy) = (x as usize, y as usize);
+        let mut next = Vec::new();
+
+        if y > 0 && state.board[0][[ux, uy]] != 1 {
+            next.push((x, y - 1));
+        }
+        if y < 8 && state.board[0][[ux, uy + 1]] != 1 {
+            next.push((x, y + 1));
+        }
+        if x > 0 && state.board[1][[ux, uy]] != 1 {
+            next.push((x - 1, y));
+        }
+        if x < 8 && state.board[1][[ux + 1, uy]] != 1 {
+            next.push((x + 1, y));
+        }
+
+        for (x, y) in next {
+            if !visited[x as usize][y as usize] {
+                visited[x as usize][y as usize] = true;
+                queue.push_back((x, y));
+            }
+        }
+    }
+
+    false
+}
+
+fn is_pawns_can_win(state: &puoribor::State) -> bool {
+    is_pawn_can_win(0, state) && is_pawn_can_win(1, state)
+}
+
+#[test]
+fn cache_is_same_to_original_check() {
+    for seed in 0..10 {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        for (state, agent_id, _) in random_game(seed).0 {
+            let cache = ReachabilityCache::new(&state);
+
+            assert!(cache.is_pawns_can_win(&state));
+
+            // put random walls on the board directly, which may trap the pawns
+            let mut walled = state.clone();
+            for _ in 0..30 {
+                let channel = rng.gen_range(0..2);
+                let (x, y) = (rng.gen_range(0..9), rng.gen_range(0..9));
+                walled.board[channel][[x, y]] = 1;
+
+                assert_eq!(
+                    cache.is_pawns_can_win(&walled),
+                    is_pawns_can_win(&walled),
+                    "\n{}",
+                    walled
+                );
+            }
+
+            // the cache is not used for the moved pawns
+            let mut moved = walled.clone();
+            moved.players[agent_id] = (rng.gen_range(0..9), rng.gen_range(0..9));
+            assert_eq!(cache.is_pawns_can_win(&moved), is_pawns_can_win(&moved));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, GameRecord, GameResult, ReplayError, StepError},
+};
+
+mod common;
+
+use common::random_record;
+
+#[test]
+fn replay_random_games() {
+    for seed in 0..10 {
+        let record = random_record(seed);
+        let states = record.replay().collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(states.len(), record.actions.len() + 1);
+        assert_eq!(states[0], record.initial_state);
+        assert_eq!(states.last().unwrap().is_win(), record.result);
+        assert_eq!(record.validate().unwrap(), *states.last().unwrap());
+
+        let replayed =
+            GameRecord::from_actions(record.initial_state.clone(), record.actions.clone()).unwrap();
+        assert_eq!(replayed.result, record.result);
+    }
+}
+
+#[test]
+fn validate_reports_first_illegal_ply() {
+    let mut record = GameRecord::new(puoribor::Env::initialize_state());
+    record.push(0, Action::new(0, (4, 1)));
+    record.push(1, Action::new(0, (4, 7)));
+    record.push(0, Action::new(0, (4, 3)));
+    record.push(1, Action::new(0, (4, 5)));
+
+    assert_eq!(
+        record.validate(),
+        Err(ReplayError::IllegalPly {
+            ply: 2,
+            error: StepError::IllegalJump {
+                agent_id: 0,
+                action: Action::new(0, (4, 3)),
+            },
+        })
+    );
+
+    // the replay is stopped after the error
+    let states = record.replay().collect::<Vec<_>>();
+    assert_eq!(states.len(), 4);
+    assert!(states[3].is_err());
+}
+
+#[test]
+fn validate_reports_wrong_result() {
+    let mut record = random_record(0);
+    let result = record.result;
+    let winner = result.winner().unwrap();
+
+    record.result = GameResult::Ongoing;
+    assert_eq!(
+        record.validate(),
+        Err(ReplayError::ResultMismatch {
+            recorded: GameResult::Ongoing,
+            replayed: result,
+        })
+    );
+
+    // the action after the game is over
+    record.result = result;
+    record.push(1 - winner, Action::new(0, (0, 0)));
+    assert_eq!(
+        record.validate(),
+        Err(ReplayError::AfterGameOver {
+            ply: record.actions.len() - 1
+        })
+    );
+}
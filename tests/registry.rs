@@ -0,0 +1,62 @@
+use fights::{
+    envs::{self, BaseEnv, RegistryError},
+    puoribor,
+};
+
+#[test]
+fn env_id() {
+    assert_eq!(puoribor::Env::env_id(), ("puoribor".to_string(), 2));
+    assert_eq!(puoribor::Env::id(), "puoribor-v2");
+
+    assert!(puoribor::Env::is_compatible("puoribor-v2"));
+    // the version 1 allowed the actions out of turn
+    assert!(!puoribor::Env::is_compatible("puoribor-v1"));
+    assert!(!puoribor::Env::is_compatible("puoribor-v3"));
+    assert!(!puoribor::Env::is_compatible("quoridor-v1"));
+}
+
+#[test]
+fn parse_env_id() {
+    assert_eq!(
+        envs::parse_env_id("puoribor-v1"),
+        Some(("puoribor".to_string(), 1))
+    );
+    assert_eq!(
+        envs::parse_env_id("some-game-v12"),
+        Some(("some-game".to_string(), 12))
+    );
+    assert_eq!(envs::parse_env_id("puoribor"), None);
+    assert_eq!(envs::parse_env_id("-v1"), None);
+    assert_eq!(envs::parse_env_id("puoribor-vx"), None);
+}
+
+#[test]
+fn spec_from_registry() {
+    let ids = envs::registry()
+        .iter()
+        .map(|spec| spec.id())
+        .collect::<Vec<_>>();
+    assert!(ids.contains(&puoribor::Env::id()));
+
+    let spec = envs::spec("puoribor-v2").unwrap();
+    assert_eq!(
+        (spec.name.to_string(), spec.version),
+        puoribor::Env::env_id()
+    );
+
+    assert_eq!(
+        envs::spec("puoribor-v1").unwrap_err(),
+        RegistryError::UnknownVersion {
+            name: "puoribor".to_string(),
+            version: 1
+        }
+    );
+    assert_eq!(
+        envs::spec("quoridor-v1").unwrap_err(),
+        RegistryError::UnknownEnv("quoridor".to_string())
+    );
+    assert_eq!(
+        envs::spec("puoribor").unwrap_err(),
+        RegistryError::InvalidId("puoribor".to_string())
+    );
+}
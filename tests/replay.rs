@@ -3,34 +3,34 @@ use std::{thread, time::Duration};
 use fights::{envs::BaseEnv, puoribor};
 
 fn play_from_log(actions: Vec<(u8, u8, u8)>, delay: u64) {
+    // the agent of each action is the agent to move, by stepping the actions
     let mut state = puoribor::Env::initialize_state();
+    let actions = actions
+        .into_iter()
+        .map(|action| {
+            let agent_id = state.to_move();
+            let action = puoribor::Action::new(action.0, (action.1, action.2));
+            state = puoribor::Env::step(state.clone(), agent_id, action.clone())
+                .unwrap_or_else(|err| unreachable!("{}", err));
 
-    for (iter, action) in actions.iter().enumerate() {
-        thread::sleep(Duration::from_millis(delay));
+            (agent_id, action)
+        })
+        .collect();
+
+    let record = puoribor::GameRecord::from_actions(puoribor::Env::initialize_state(), actions)
+        .unwrap_or_else(|err| unreachable!("{}", err));
 
-        let agent_id = iter % 2;
+    for (state, (agent_id, action)) in record.replay().map(Result::unwrap).zip(&record.actions) {
+        thread::sleep(Duration::from_millis(delay));
 
         print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
         println!("{}", state);
         println!("Player {} will do: {:?}", agent_id, action);
-
-        let action = puoribor::Action::new(action.0, (action.1, action.2));
-
-        match puoribor::Env::step(state.clone(), agent_id, action) {
-            Ok(new_state) => {
-                state = new_state;
-            }
-            Err(err_reason) => unreachable!("{}", err_reason),
-        }
     }
 
     print!("{esc}[2J{esc}[1;1H", esc = 27 as char); // clear terminal
-    println!("{}", state);
-    if state.is_win() == 0 {
-        println!("The player 0 is won!");
-    } else {
-        println!("The player 1 is won!");
-    }
+    println!("{}", record.validate().unwrap());
+    println!("The result is {:?}.", record.result);
 }
 
 #[test]
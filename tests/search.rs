@@ -0,0 +1,152 @@
+use std::time::{Duration, Instant};
+
+use fights::{
+    envs::BaseEnv,
+    puoribor::{
+        self, Action, Agent, AlphaBeta, DrawRules, GameResult, RandomAgent, SearchLimits, WIN_SCORE,
+    },
+};
+
+fn limits(depth: usize) -> SearchLimits {
+    SearchLimits {
+        depth,
+        ..SearchLimits::default()
+    }
+}
+
+// check if the actions are legal in a row from the state
+fn assert_pv_is_legal(state: &puoribor::State, pv: &[Action]) {
+    pv.iter().fold(state.clone(), |state, action| {
+        let agent_id = state.to_move();
+        puoribor::Env::step(state, agent_id, action.clone()).unwrap()
+    });
+}
+
+#[test]
+fn search_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    for depth in 1..=2 {
+        let result = AlphaBeta::new(limits(depth)).search(&state).unwrap();
+
+        assert_eq!(result.depth, depth);
+        assert_eq!(result.pv.len(), depth);
+        assert_eq!(result.pv[0], result.best_action);
+        assert_pv_is_legal(&state, &result.pv);
+    }
+}
+
+#[test]
+fn search_without_action() {
+    let mut agent = AlphaBeta::new(limits(2));
+
+    // the game is over
+    let mut state = puoribor::Env::initialize_state();
+    state.players[0] = (4, 8);
+    state.refresh_zobrist();
+    assert_eq!(agent.search(&state), None);
+    assert_eq!(agent.act(&state, 0), None);
+
+    // the pawn is boxed by the walls without any remaining wall, which cannot be made by the rules
+    let mut state = puoribor::Env::initialize_state();
+    state.board[0][[4, 1]] = 1;
+    state.board[1][[4, 0]] = 1;
+    state.board[1][[5, 0]] = 1;
+    state.remaining_walls = [0, 10];
+    state.refresh_zobrist();
+    assert!(puoribor::Env::legal_actions(&state, 0).is_empty());
+    assert_eq!(agent.search(&state), None);
+    assert_eq!(agent.act(&state, 0), None);
+}
+
+#[test]
+fn find_the_win() {
+    let mut state = puoribor::Env::initialize_state();
+    // the agent 0 wins in 3 plies, while the agent 1 cannot block it without walls
+    state.players = [(4, 6), (0, 4)];
+    state.remaining_walls = [10, 0];
+    state.refresh_zobrist();
+
+    let result = AlphaBeta::new(limits(4)).search(&state).unwrap();
+    assert_eq!(result.score, WIN_SCORE - 3);
+    assert_eq!(result.pv.len(), 3);
+    assert_pv_is_legal(&state, &result.pv);
+
+    // the win of the next ply is found on any depth
+    state.players = [(4, 7), (0, 4)];
+    state.refresh_zobrist();
+    let result = AlphaBeta::new(limits(1)).search(&state).unwrap();
+    assert_eq!(result.best_action, Action::new(0, (4, 8)));
+    assert_eq!(result.score, WIN_SCORE - 1);
+
+    // there is no action on the game over
+    state.players = [(4, 8), (0, 4)];
+    state.refresh_zobrist();
+    assert!(AlphaBeta::new(limits(1)).search(&state).is_none());
+}
+
+#[test]
+fn same_result_with_transposition_table() {
+    let state = puoribor::Env::initialize_state();
+    let mut searcher = AlphaBeta::new(limits(2));
+
+    let result = searcher.search(&state).unwrap();
+    let again = searcher.search(&state).unwrap();
+
+    // the table makes the search faster, but the result is same
+    assert_eq!(again.score, result.score);
+    assert!(again.nodes < result.nodes);
+
+    searcher.clear();
+    assert_eq!(searcher.search(&state).unwrap(), result);
+}
+
+#[test]
+fn search_within_limits() {
+    let state = puoribor::Env::initialize_state();
+
+    let result = AlphaBeta::new(SearchLimits {
+        depth: 10,
+        time: None,
+        nodes: Some(1000),
+    })
+    .search(&state)
+    .unwrap();
+    assert!(result.depth < 10);
+    assert_pv_is_legal(&state, &result.pv);
+
+    let started = Instant::now();
+    let result = AlphaBeta::new(SearchLimits {
+        depth: 10,
+        time: Some(Duration::from_millis(200)),
+        nodes: None,
+    })
+    .search(&state)
+    .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(result.depth >= 1);
+}
+
+#[test]
+fn alpha_beta_beats_random_agent() {
+    for seed in 0..4 {
+        let mut state = puoribor::Env::initialize_state();
+        state.set_draw_rules(DrawRules::standard());
+
+        let mut agents: [Box<dyn Agent>; 2] = [
+            Box::new(AlphaBeta::new(limits(1))),
+            Box::new(RandomAgent::seeded(seed)),
+        ];
+        if seed % 2 == 1 {
+            agents.swap(0, 1);
+        }
+
+        while !state.is_win().is_over() {
+            let agent_id = state.to_move();
+            let action = agents[agent_id].act(&state, agent_id).unwrap();
+            state = puoribor::Env::step(state, agent_id, action).unwrap();
+        }
+
+        assert_eq!(state.is_win(), GameResult::Win(seed as usize % 2));
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fs, io::ErrorKind};
+
+use fights::puoribor::{
+    self, read_shard, self_play, self_play_with, write_shard, write_shards, Action, DrawRules,
+    GameResult, MctsConfig, SelfPlayConfig, SHARD_VERSION,
+};
+
+fn config(games: usize) -> SelfPlayConfig {
+    SelfPlayConfig {
+        games,
+        mcts: MctsConfig {
+            simulations: 10,
+            ..MctsConfig::default()
+        },
+        draw_rules: DrawRules {
+            max_ply: Some(30),
+            repetition: Some(3),
+        },
+        sampling_plies: 4,
+        seed: 0,
+    }
+}
+
+#[test]
+fn self_play_games() {
+    let games = self_play(&config(4));
+    assert_eq!(games.len(), 4);
+
+    for (index, game) in games.iter().enumerate() {
+        assert_eq!(game.game, index);
+        assert_eq!(game.samples.len(), game.record.actions.len());
+
+        let states = game.record.replay().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(game.record.result.is_over());
+        assert_eq!(states.last().unwrap().is_win(), game.record.result);
+
+        for ((sample, state), (agent_id, action)) in
+            game.samples.iter().zip(&states).zip(&game.record.actions)
+        {
+            assert_eq!(sample.game, index);
+            assert_eq!(sample.ply, state.ply());
+            assert_eq!(sample.agent_id, *agent_id);
+            assert_eq!(sample.observation, state.to_observation(*agent_id, false));
+
+            // the action is always searched
+            assert!((sample.policy.iter().sum::<f32>() - 1.0).abs() < 1e-4);
+            assert!(sample.policy[action.to_index().unwrap()] > 0.0);
+
+            let outcome = match game.record.result {
+                GameResult::Win(winner) if winner == *agent_id => 1.0,
+                GameResult::Win(_) => -1.0,
+                _ => 0.0,
+            };
+            assert_eq!(sample.outcome, outcome);
+        }
+    }
+
+    // the games are same for the same config, but different between the games
+    assert_eq!(self_play(&config(4)), games);
+    assert_ne!(games[0].record.actions, games[1].record.actions);
+}
+
+#[test]
+fn self_play_with_prior_value() {
+    let games = self_play_with(&config(2), |_| {
+        |_: &puoribor::State, actions: &[Action]| (vec![1.0; actions.len()], 0.0)
+    });
+
+    assert_eq!(games.len(), 2);
+    assert!(games.iter().all(|game| game.record.validate().is_ok()));
+}
+
+#[test]
+fn shard_round_trip() {
+    let games = self_play(&config(3));
+    let samples = games
+        .iter()
+        .flat_map(|game| game.samples.clone())
+        .collect::<Vec<_>>();
+
+    let mut shard = Vec::new();
+    write_shard(&mut shard, &games).unwrap();
+    assert_eq!(shard.len(), 16 + samples.len() * 3901);
+    assert_eq!(read_shard(&shard[..]).unwrap(), samples);
+
+    let mut broken = shard.clone();
+    broken[0] = b'J';
+    assert_eq!(
+        read_shard(&broken[..]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    // the version is not known
+    let mut unknown = shard.clone();
+    unknown[4..8].copy_from_slice(&(SHARD_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        read_shard(&unknown[..]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    assert_eq!(
+        read_shard(&shard[..shard.len() - 1]).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+
+    let dir = std::env::temp_dir().join(format!("fights-shards-{}", std::process::id()));
+    let paths = write_shards(&dir, &games, 2).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("shard-00000.prbs"));
+
+    let read = paths
+        .iter()
+        .flat_map(|path| read_shard(fs::File::open(path).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(read, samples);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,174 @@
+use std::fs;
+
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, GameRecord, GameResult},
+};
+use serde_json::{json, Value};
+
+mod common;
+
+fn play(actions: &[(usize, (u8, u8, u8))]) -> GameRecord {
+    GameRecord::from_actions(puoribor::Env::initialize_state(), common::actions(actions)).unwrap()
+}
+
+// the golden states are generated by the original Python environment with tests/golden/generate.py
+fn golden(name: &str) -> Value {
+    let path = format!("{}/tests/golden/{}", env!("CARGO_MANIFEST_DIR"), name);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+// the game with every kind of actions, which has the wall split by the rotation
+fn sample_game() -> GameRecord {
+    let mut record = play(&[
+        (0, (1, 3, 1)),
+        (1, (3, 0, 0)),
+        (0, (2, 6, 4)),
+        (1, (0, 4, 7)),
+        (0, (0, 5, 0)),
+    ]);
+    record
+        .metadata
+        .insert("event".to_string(), "golden".to_string());
+
+    record
+}
+
+#[test]
+fn state_to_json() {
+    let record = sample_game();
+    let state = record.validate().unwrap();
+
+    assert_eq!(
+        serde_json::to_value(puoribor::Env::initialize_state()).unwrap(),
+        golden("initial_state.json")
+    );
+
+    // the golden is from the original, which does not have the turn
+    let mut value = serde_json::to_value(&state).unwrap();
+    let object = value.as_object_mut().unwrap();
+    assert_eq!(object.remove("to_move"), Some(json!(1)));
+    assert_eq!(object.remove("ply"), Some(json!(5)));
+    assert_eq!(value, golden("state.json"));
+
+    let mut parsed: puoribor::State = serde_json::from_value(golden("state.json")).unwrap();
+    assert_eq!((parsed.to_move(), parsed.ply()), (0, 0));
+
+    parsed.set_turn(state.to_move(), state.ply());
+    assert_eq!(parsed, state);
+    assert_eq!(parsed.zobrist(), state.zobrist());
+}
+
+#[test]
+fn state_with_turn() {
+    let state = sample_game().validate().unwrap();
+
+    let parsed: puoribor::State =
+        serde_json::from_value(serde_json::to_value(&state).unwrap()).unwrap();
+    assert_eq!((parsed.to_move(), parsed.ply()), (1, 5));
+    assert_eq!(parsed, state);
+    assert_eq!(parsed.zobrist(), state.zobrist());
+
+    // the agent to move can act on the parsed state
+    let actions = puoribor::Env::legal_actions(&parsed, 1);
+    assert!(!actions.is_empty());
+    assert!(puoribor::Env::step(parsed, 1, actions[0].clone()).is_ok());
+
+    let mut value = golden("state.json");
+    value["to_move"] = json!(2);
+    assert!(serde_json::from_value::<puoribor::State>(value).is_err());
+}
+
+#[test]
+fn action_to_json() {
+    assert_eq!(
+        serde_json::to_value(Action::new(0, (4, 1))).unwrap(),
+        json!([0, 4, 1])
+    );
+    assert_eq!(
+        serde_json::to_value(Action::new(1, (3, 1))).unwrap(),
+        json!([1, 3, 0])
+    );
+    assert_eq!(
+        serde_json::to_value(Action::new(2, (6, 4))).unwrap(),
+        json!([2, 5, 4])
+    );
+    assert_eq!(
+        serde_json::to_value(Action::new(3, (0, 0))).unwrap(),
+        json!([3, 0, 0])
+    );
+
+    for action in (0..Action::SPACE_SIZE).filter_map(Action::from_index) {
+        let value = serde_json::to_value(&action).unwrap();
+        assert_eq!(serde_json::from_value::<Action>(value).unwrap(), action);
+    }
+
+    assert!(serde_json::from_value::<Action>(json!([4, 0, 0])).is_err());
+    assert!(serde_json::from_value::<Action>(json!([2, 15, 0])).is_err());
+    assert!(serde_json::from_value::<Action>(json!([0, -1, 0])).is_err());
+}
+
+#[test]
+fn result_to_json() {
+    for (result, value) in [
+        (GameResult::Ongoing, json!(null)),
+        (GameResult::Win(0), json!(0)),
+        (GameResult::Win(1), json!(1)),
+        (GameResult::Draw, json!("draw")),
+    ] {
+        assert_eq!(serde_json::to_value(result).unwrap(), value);
+        assert_eq!(serde_json::from_value::<GameResult>(value).unwrap(), result);
+    }
+
+    assert!(serde_json::from_value::<GameResult>(json!(2)).is_err());
+    assert!(serde_json::from_value::<GameResult>(json!("Ongoing")).is_err());
+}
+
+#[test]
+fn game_record_to_json() {
+    let record = sample_game();
+
+    assert_eq!(serde_json::to_value(&record).unwrap(), golden("game.json"));
+    assert_eq!(
+        serde_json::from_value::<GameRecord>(golden("game.json")).unwrap(),
+        record
+    );
+}
+
+#[test]
+fn reject_incompatible_record() {
+    for env in ["puoribor-v1", "quoridor-v2", "puoribor"] {
+        let mut value = golden("game.json");
+        value["env"] = json!(env);
+        assert!(serde_json::from_value::<GameRecord>(value).is_err());
+    }
+
+    let mut value = golden("game.json");
+    value.as_object_mut().unwrap().remove("env");
+    assert!(serde_json::from_value::<GameRecord>(value).is_err());
+}
+
+#[test]
+fn reject_invalid_state() {
+    let mut value = golden("initial_state.json");
+    assert!(serde_json::from_value::<puoribor::State>(value.clone()).is_ok());
+
+    // the pin without its walls
+    value["board"][4][3][3] = json!(1);
+    assert!(serde_json::from_value::<puoribor::State>(value.clone()).is_err());
+
+    // two pawns of agent 0
+    let mut value = golden("initial_state.json");
+    value["board"][0][0][0] = json!(1);
+    assert!(serde_json::from_value::<puoribor::State>(value).is_err());
+
+    // the board without the channel of vertical pins
+    let mut value = golden("initial_state.json");
+    value["board"].as_array_mut().unwrap().pop();
+    assert!(serde_json::from_value::<puoribor::State>(value).is_err());
+
+    // more walls than the game gives
+    let mut value = golden("initial_state.json");
+    value["walls_remaining"] = json!([20, 10]);
+    assert!(serde_json::from_value::<puoribor::State>(value).is_err());
+}
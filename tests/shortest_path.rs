@@ -0,0 +1,84 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, GameResult},
+};
+
+mod common;
+
+use common::{analysis_state, play_from, random_game};
+
+// follow the path by moving only the pawn of agent, then check if it wins
+fn assert_path_is_legal(state: &puoribor::State, agent_id: usize, path: &[(u8, u8)]) {
+    let mut state = state.clone();
+    state.set_analysis(true);
+
+    let state = path.iter().fold(state, |state, &pos| {
+        puoribor::Env::step(state, agent_id, Action::new(0, pos)).unwrap()
+    });
+
+    assert_eq!(state.is_win(), GameResult::Win(agent_id));
+}
+
+#[test]
+fn shortest_path_of_initial_state() {
+    let state = puoribor::Env::initialize_state();
+
+    for agent_id in 0..2 {
+        let path = puoribor::Env::shortest_path(&state, agent_id).unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert_eq!(puoribor::Env::shortest_path_len(&state, agent_id), Some(8));
+        assert_path_is_legal(&state, agent_id, &path);
+    }
+}
+
+#[test]
+fn shortest_path_around_walls() {
+    let state = play_from(analysis_state(), &[(1, (1, 3, 1)), (1, (1, 5, 1))]);
+
+    // the horizontal walls from x = 3 to x = 6 between y = 0 and y = 1 block both pawns
+    for agent_id in 0..2 {
+        let path = puoribor::Env::shortest_path(&state, agent_id).unwrap();
+
+        assert_eq!(path.len(), 10);
+        assert_path_is_legal(&state, agent_id, &path);
+    }
+}
+
+#[test]
+fn shortest_path_with_jump() {
+    let state = play_from(
+        analysis_state(),
+        &[
+            (0, (0, 4, 1)),
+            (0, (0, 4, 2)),
+            (0, (0, 4, 3)),
+            (1, (0, 4, 7)),
+            (1, (0, 4, 6)),
+            (1, (0, 4, 5)),
+            (1, (0, 4, 4)),
+        ],
+    );
+
+    assert_eq!(
+        puoribor::Env::shortest_path(&state, 0),
+        Some(vec![(4, 5), (4, 6), (4, 7), (4, 8)])
+    );
+    assert_eq!(
+        puoribor::Env::shortest_path(&state, 1),
+        Some(vec![(4, 2), (4, 1), (4, 0)])
+    );
+}
+
+#[test]
+fn shortest_path_of_random_games() {
+    for seed in 0..10 {
+        for (state, _, _) in random_game(seed).0 {
+            for agent_id in 0..2 {
+                if let Some(path) = puoribor::Env::shortest_path(&state, agent_id) {
+                    assert_path_is_legal(&state, agent_id, &path);
+                }
+            }
+        }
+    }
+}
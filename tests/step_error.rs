@@ -0,0 +1,133 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action, StepError},
+};
+
+mod common;
+
+use common::analysis_state;
+
+fn step(
+    state: &puoribor::State,
+    agent_id: usize,
+    action: (u8, u8, u8),
+) -> Result<puoribor::State, StepError> {
+    puoribor::Env::step(
+        state.clone(),
+        agent_id,
+        Action::new(action.0, (action.1, action.2)),
+    )
+}
+
+#[test]
+fn move_errors() {
+    let state = analysis_state();
+
+    assert!(matches!(
+        step(&state, 0, (0, 9, 0)),
+        Err(StepError::OutOfBoard { .. })
+    ));
+    assert!(matches!(
+        step(&state, 0, (0, 4, 3)),
+        Err(StepError::InvalidDistance { .. })
+    ));
+    assert!(matches!(
+        step(&state, 0, (0, 4, 2)),
+        Err(StepError::IllegalJump { .. })
+    ));
+
+    let walled = step(&state, 1, (1, 3, 1)).unwrap();
+    assert!(matches!(
+        step(&walled, 0, (0, 4, 1)),
+        Err(StepError::BlockedByWall { .. })
+    ));
+
+    let mut facing = state;
+    facing.players = [(4, 4), (4, 5)];
+    assert!(matches!(
+        step(&facing, 0, (0, 4, 5)),
+        Err(StepError::OverlapPawn { .. })
+    ));
+    assert!(matches!(
+        step(&facing, 0, (0, 3, 5)),
+        Err(StepError::IllegalJump { .. })
+    ));
+    assert!(step(&facing, 0, (0, 4, 6)).is_ok());
+
+    let behind = step(&facing, 1, (1, 4, 6)).unwrap();
+    assert!(matches!(
+        step(&behind, 0, (0, 4, 6)),
+        Err(StepError::BlockedByWall { .. })
+    ));
+    assert!(step(&behind, 0, (0, 3, 5)).is_ok());
+}
+
+#[test]
+fn wall_errors() {
+    let state = analysis_state();
+
+    assert!(matches!(
+        step(&state, 0, (1, 8, 1)),
+        Err(StepError::OutOfBoard { .. })
+    ));
+    assert!(matches!(
+        step(&state, 0, (2, 0, 1)),
+        Err(StepError::OutOfBoard { .. })
+    ));
+    assert!(matches!(
+        step(&state, 0, (3, 6, 0)),
+        Err(StepError::OutOfBoard { .. })
+    ));
+
+    let walled = step(&state, 0, (1, 3, 1)).unwrap();
+    assert!(matches!(
+        step(&walled, 0, (1, 4, 1)),
+        Err(StepError::OverlapWall { .. })
+    ));
+    assert!(matches!(
+        step(&walled, 0, (2, 4, 0)),
+        Err(StepError::Intersecting { .. })
+    ));
+
+    let mut empty = state.clone();
+    empty.remaining_walls = [0, 1];
+    assert!(matches!(
+        step(&empty, 0, (1, 0, 1)),
+        Err(StepError::NoRemainingWalls { .. })
+    ));
+    assert!(matches!(
+        step(&empty, 1, (3, 0, 0)),
+        Err(StepError::NoRemainingWalls { .. })
+    ));
+}
+
+#[test]
+fn trap_error() {
+    let mut state = analysis_state();
+
+    for action in [(1, 3, 1), (2, 3, 0)] {
+        state = step(&state, 1, action).unwrap();
+    }
+
+    let error = step(&state, 1, (2, 5, 0)).unwrap_err();
+    assert!(matches!(error, StepError::WouldTrapPawn { .. }));
+    assert_eq!(error.agent_id(), 1);
+    assert_eq!(error.action(), &Action::new(2, (5, 0)));
+    assert!(error.to_string().starts_with("PlaceWallVertically: "));
+}
+
+#[test]
+fn out_of_turn_error() {
+    let state = puoribor::Env::initialize_state();
+
+    let error = step(&state, 1, (0, 4, 7)).unwrap_err();
+    assert!(matches!(error, StepError::OutOfTurn { .. }));
+    assert_eq!(error.agent_id(), 1);
+
+    let state = step(&state, 0, (0, 4, 1)).unwrap();
+    assert!(matches!(
+        step(&state, 0, (0, 4, 2)),
+        Err(StepError::OutOfTurn { .. })
+    ));
+    assert!(step(&state, 1, (0, 4, 7)).is_ok());
+}
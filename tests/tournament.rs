@@ -0,0 +1,231 @@
+use fights::puoribor::{
+    Action, Agent, DrawRules, Format, GameOutcome, GameResult, GreedyAgent, RandomAgent, State,
+    Tournament, TournamentConfig, TournamentError, TournamentResult,
+};
+
+// the agent moving out of the board, which always forfeits
+struct IllegalAgent;
+
+impl Agent for IllegalAgent {
+    fn act(&mut self, _: &State, _: usize) -> Option<Action> {
+        Some(Action::new(0, (9, 9)))
+    }
+}
+
+fn config(format: Format, games_per_pair: usize) -> TournamentConfig {
+    TournamentConfig {
+        format,
+        games_per_pair,
+        draw_rules: DrawRules {
+            max_ply: Some(60),
+            repetition: Some(3),
+        },
+    }
+}
+
+// the agent panicking on its first action
+struct PanicAgent;
+
+impl Agent for PanicAgent {
+    fn act(&mut self, _: &State, _: usize) -> Option<Action> {
+        panic!("the agent is broken.");
+    }
+}
+
+// the agent without any action
+struct PassAgent;
+
+impl Agent for PassAgent {
+    fn act(&mut self, _: &State, _: usize) -> Option<Action> {
+        None
+    }
+}
+
+// the tournament of the greedy agent, the seeded random agent and the illegal agent
+fn tournament(format: Format, games_per_pair: usize) -> Tournament {
+    let mut tournament = Tournament::new(config(format, games_per_pair));
+
+    tournament.add("greedy", || Box::new(GreedyAgent::new()));
+
+    let mut seed = 0;
+    tournament.add("random", move || {
+        seed += 1;
+        Box::new(RandomAgent::seeded(seed))
+    });
+
+    tournament.add("illegal", || Box::new(IllegalAgent));
+
+    tournament
+}
+
+#[test]
+fn round_robin_alternates_first_player() {
+    let result = tournament(Format::RoundRobin, 4).run().unwrap();
+
+    assert_eq!(result.games.len(), 3 * 4);
+    for (a, b) in [(0, 1), (0, 2), (1, 2)] {
+        let firsts = result
+            .games
+            .iter()
+            .filter(|game| game.players == [a, b])
+            .count();
+        let seconds = result
+            .games
+            .iter()
+            .filter(|game| game.players == [b, a])
+            .count();
+
+        assert_eq!((firsts, seconds), (2, 2));
+    }
+
+    for standing in &result.standings {
+        assert_eq!(standing.games(), 8);
+    }
+}
+
+#[test]
+fn gauntlet_plays_only_challenger() {
+    let mut tournament = tournament(Format::Gauntlet { challenger: 1 }, 2);
+    assert_eq!(tournament.pairs(), Ok(vec![(1, 0), (1, 2)]));
+
+    let result = tournament.run().unwrap();
+
+    assert_eq!(result.games.len(), 4);
+    assert!(result.games.iter().all(|game| game.players.contains(&1)));
+    assert_eq!(result.standings[1].games(), 4);
+    assert_eq!(result.score_between(0, 2), (0.0, 0));
+}
+
+#[test]
+fn illegal_actions_are_forfeits() {
+    let result = tournament(Format::RoundRobin, 2).run().unwrap();
+    let illegal = &result.standings[2];
+
+    assert_eq!((illegal.wins, illegal.draws, illegal.losses), (0, 0, 4));
+    assert_eq!(illegal.forfeits, 4);
+    assert_eq!(result.standings[0].forfeits, 0);
+    assert_eq!(result.standings[1].forfeits, 0);
+
+    for game in result.games.iter().filter(|game| game.players.contains(&2)) {
+        let winner = game.result.winner().unwrap();
+
+        assert!(game.is_forfeit);
+        assert_ne!(game.players[winner], 2);
+        assert!(game.plies <= 1);
+    }
+}
+
+#[test]
+fn panics_and_passes_are_forfeits() {
+    let mut tournament = Tournament::new(config(Format::RoundRobin, 2));
+    tournament.add("greedy", || Box::new(GreedyAgent::new()));
+    tournament.add("panic", || Box::new(PanicAgent));
+    tournament.add("pass", || Box::new(PassAgent));
+
+    // the games after the panics are played
+    let result = tournament.run().unwrap();
+    assert_eq!(result.games.len(), 6);
+
+    for standing in &result.standings[1..] {
+        assert_eq!(standing.losses, standing.forfeits);
+    }
+    // the first agent between them forfeits
+    assert_eq!(result.standings[0].wins, 4);
+    assert_eq!(result.standings[1].forfeits, 3);
+    assert_eq!(result.standings[2].forfeits, 3);
+}
+
+#[test]
+fn reject_unknown_challenger() {
+    let mut tournament = tournament(Format::Gauntlet { challenger: 3 }, 2);
+    let error = TournamentError::UnknownChallenger {
+        challenger: 3,
+        participants: 3,
+    };
+
+    assert_eq!(tournament.pairs(), Err(error.clone()));
+    assert_eq!(tournament.run().unwrap_err(), error);
+}
+
+// the outcome of the game won by the winner, who played as the agent 0
+fn win(winner: usize, loser: usize) -> GameOutcome {
+    GameOutcome {
+        players: [winner, loser],
+        result: GameResult::Win(0),
+        is_forfeit: false,
+        plies: 10,
+    }
+}
+
+fn draw(a: usize, b: usize) -> GameOutcome {
+    GameOutcome {
+        players: [a, b],
+        result: GameResult::Draw,
+        is_forfeit: false,
+        plies: 200,
+    }
+}
+
+fn names(count: usize) -> Vec<String> {
+    (0..count).map(|index| format!("agent{}", index)).collect()
+}
+
+#[test]
+fn elo_of_even_score_is_zero() {
+    let result =
+        TournamentResult::from_games(names(2), vec![win(0, 1), win(1, 0), draw(0, 1), draw(1, 0)]);
+
+    for standing in &result.standings {
+        assert!(standing.elo.abs() < 1e-6);
+        assert!(standing.elo_interval > 0.0);
+    }
+    assert_eq!(result.score_between(0, 1), (2.0, 4));
+}
+
+#[test]
+fn elo_follows_score_and_narrows_with_games() {
+    // 3:1 is about 191 Elo without the virtual draws
+    let games = |repeat: usize| {
+        (0..repeat)
+            .flat_map(|_| vec![win(0, 1), win(0, 1), win(1, 0), win(0, 1)])
+            .collect::<Vec<_>>()
+    };
+
+    let few = TournamentResult::from_games(names(2), games(1));
+    let many = TournamentResult::from_games(names(2), games(25));
+
+    let difference = |result: &TournamentResult| result.standings[0].elo - result.standings[1].elo;
+    assert!(difference(&few) > 0.0);
+    assert!(difference(&few) < difference(&many));
+    assert!((difference(&many) - 191.0).abs() < 20.0);
+    assert!(many.standings[0].elo_interval < few.standings[0].elo_interval);
+
+    assert!((many.standings[0].elo + many.standings[1].elo).abs() < 1e-6);
+}
+
+#[test]
+fn perfect_score_has_finite_elo() {
+    let result = TournamentResult::from_games(names(3), vec![win(0, 1), win(0, 2), win(1, 2)]);
+    let elos = result
+        .standings
+        .iter()
+        .map(|standing| standing.elo)
+        .collect::<Vec<_>>();
+
+    assert!(elos.iter().all(|elo| elo.is_finite()));
+    assert!(elos[0] > elos[1] && elos[1] > elos[2]);
+}
+
+#[test]
+fn crosstable_is_sorted_by_elo() {
+    let result = tournament(Format::RoundRobin, 2).run().unwrap();
+    let table = result.to_string();
+    let lines = table.lines().collect::<Vec<_>>();
+
+    println!("{}", table);
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].contains("elo") && lines[0].contains("forfeits"));
+    assert!(lines[3].contains("illegal"));
+    assert!(lines[3].contains("0-0-4"));
+    assert!(lines[3].contains("0/2"));
+}
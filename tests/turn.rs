@@ -0,0 +1,57 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action},
+};
+
+mod common;
+
+use common::random_actions;
+
+#[test]
+fn turn_of_random_games() {
+    for seed in 0..5 {
+        let mut state = puoribor::Env::initialize_state();
+        let mut undos = Vec::new();
+
+        for (agent_id, action) in random_actions(seed) {
+            assert_eq!(agent_id, state.to_move());
+            assert_eq!(agent_id, state.ply() % 2);
+
+            // the other agent cannot act
+            assert!(puoribor::Env::legal_actions(&state, 1 - agent_id).is_empty());
+
+            undos.push((state.clone(), state.apply(agent_id, &action).unwrap()));
+        }
+
+        while let Some((before, undo)) = undos.pop() {
+            state.undo(undo);
+            assert_eq!(state.to_move(), before.to_move());
+            assert_eq!(state.ply(), before.ply());
+        }
+
+        assert_eq!(state.ply(), 0);
+    }
+}
+
+#[test]
+fn analysis_mode() {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_analysis(true);
+
+    assert!(!puoribor::Env::legal_actions(&state, 1).is_empty());
+
+    // the turn still goes to the other agent of the last action
+    let state = puoribor::Env::step(state, 1, Action::new(0, (4, 7))).unwrap();
+    assert_eq!((state.to_move(), state.ply()), (0, 1));
+
+    let state = puoribor::Env::step(state, 1, Action::new(0, (4, 6))).unwrap();
+    assert_eq!((state.to_move(), state.ply()), (0, 2));
+    assert!(state.is_analysis());
+}
+
+#[test]
+#[should_panic]
+fn set_turn_of_third_agent() {
+    let mut state = puoribor::Env::initialize_state();
+    state.set_turn(2, 0);
+}
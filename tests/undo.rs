@@ -0,0 +1,64 @@
+use fights::{
+    envs::BaseEnv,
+    puoribor::{self, Action},
+};
+
+mod common;
+
+use common::random_actions;
+
+#[test]
+fn apply_and_undo_every_action() {
+    let mut state = puoribor::Env::initialize_state();
+
+    for (ply, (agent_id, action)) in random_actions(0).into_iter().enumerate() {
+        if ply % 3 == 0 {
+            for candidate in (0..4).flat_map(|action_type| {
+                (0..10).flat_map(move |y| (0..10).map(move |x| Action::new(action_type, (x, y))))
+            }) {
+                let mut applied = state.clone();
+                let result = applied.apply(agent_id, &candidate);
+
+                match (
+                    result,
+                    puoribor::Env::step(state.clone(), agent_id, candidate),
+                ) {
+                    (Ok(undo), Ok(stepped)) => {
+                        assert_eq!(applied, stepped);
+
+                        applied.undo(undo);
+                        assert_eq!(applied, state);
+                    }
+                    (Err(error), Err(expected)) => {
+                        assert_eq!(error, expected);
+                        assert_eq!(applied, state);
+                    }
+                    (result, expected) => panic!("{:?} != {:?}", result, expected),
+                }
+            }
+        }
+
+        let _ = state.apply(agent_id, &action).unwrap();
+    }
+}
+
+#[test]
+fn undo_whole_game() {
+    for seed in 0..10 {
+        let mut state = puoribor::Env::initialize_state();
+        let mut states = Vec::new();
+        let mut undos = Vec::new();
+
+        for (agent_id, action) in random_actions(seed) {
+            states.push(state.clone());
+            undos.push(state.apply(agent_id, &action).unwrap());
+        }
+
+        while let Some(undo) = undos.pop() {
+            state.undo(undo);
+            assert_eq!(state, states.pop().unwrap());
+        }
+
+        assert_eq!(state, puoribor::Env::initialize_state());
+    }
+}
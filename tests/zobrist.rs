@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+
+use fights::{envs::BaseEnv, puoribor};
+
+mod common;
+
+use common::{analysis_state, play_from, random_actions};
+
+fn refreshed(state: &puoribor::State) -> u64 {
+    let mut state = state.clone();
+    state.refresh_zobrist();
+    state.zobrist()
+}
+
+#[test]
+fn incremental_zobrist_of_random_games() {
+    for seed in 0..20 {
+        let mut state = puoribor::Env::initialize_state();
+        let mut hashes = vec![state.zobrist()];
+        let mut undos = Vec::new();
+
+        for (agent_id, action) in random_actions(seed) {
+            undos.push(state.apply(agent_id, &action).unwrap());
+
+            assert_eq!(state.zobrist(), refreshed(&state));
+            assert_eq!(state.flipped().zobrist(), refreshed(&state.flipped()));
+            assert_eq!(state.mirrored().zobrist(), refreshed(&state.mirrored()));
+            hashes.push(state.zobrist());
+        }
+
+        while let Some(undo) = undos.pop() {
+            hashes.pop();
+            state.undo(undo);
+            assert_eq!(state.zobrist(), *hashes.last().unwrap());
+        }
+    }
+}
+
+#[test]
+fn transpositions_have_same_zobrist() {
+    let state = play_from(
+        analysis_state(),
+        &[(0, (1, 3, 1)), (1, (2, 6, 4)), (0, (0, 5, 0))],
+    );
+    let transposed = play_from(
+        analysis_state(),
+        &[(0, (0, 5, 0)), (1, (2, 6, 4)), (0, (1, 3, 1))],
+    );
+
+    assert_eq!(state, transposed);
+    assert_eq!(state.zobrist(), transposed.zobrist());
+
+    let other = play_from(
+        analysis_state(),
+        &[(0, (1, 3, 1)), (1, (2, 6, 5)), (0, (0, 5, 0))],
+    );
+    assert_ne!(state.zobrist(), other.zobrist());
+
+    // the same board with different remaining walls
+    let other = play_from(
+        analysis_state(),
+        &[(0, (1, 3, 1)), (0, (2, 6, 4)), (0, (0, 5, 0))],
+    );
+    assert_eq!(state.board, other.board);
+    assert_ne!(state.zobrist(), other.zobrist());
+}
+
+#[test]
+fn zobrist_of_rotation_deleting_edges() {
+    // the horizontal wall on the edge of the rotated section is deleted
+    let state = play_from(analysis_state(), &[(0, (1, 0, 1)), (1, (3, 0, 0))]);
+
+    assert_eq!(state.board[0].sum(), 0);
+    assert_eq!(state.zobrist(), refreshed(&state));
+}
+
+#[test]
+fn states_in_hash_set() {
+    let mut states = HashSet::new();
+
+    // the pawns go and come back, which is the same position with the initial state
+    states.insert(puoribor::Env::initialize_state());
+    states.insert(play_from(
+        analysis_state(),
+        &[
+            (0, (0, 4, 1)),
+            (1, (0, 4, 7)),
+            (0, (0, 4, 0)),
+            (1, (0, 4, 8)),
+        ],
+    ));
+    assert_eq!(states.len(), 1);
+
+    // the agent to move is a part of the position
+    states.insert(play_from(
+        analysis_state(),
+        &[(0, (0, 4, 1)), (0, (0, 4, 0))],
+    ));
+    states.insert(play_from(analysis_state(), &[(0, (0, 4, 1))]));
+    assert_eq!(states.len(), 3);
+
+    assert!(states.contains(&play_from(
+        analysis_state(),
+        &[(1, (0, 4, 7)), (1, (0, 4, 8))]
+    )));
+}
+
+#[test]
+fn hash_set_with_modified_fields() {
+    let mut states = HashSet::new();
+    states.insert(play_from(analysis_state(), &[(0, (0, 4, 1))]));
+
+    // the zobrist hash is stale without `refresh_zobrist`, but the state is found anyway
+    let mut state = puoribor::Env::initialize_state();
+    state.players[0] = (4, 1);
+    state.set_turn(1, 1);
+
+    assert!(states.contains(&state));
+}